@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::broadcast;
+
+/// A change to a single key, delivered to `WATCH`/`WATCH_QUERY` waiters.
+/// `seq` is a monotonically increasing, database-wide sequence number so a
+/// client that supplies its last-seen `seq` can tell whether a change has
+/// already happened without racing the subscribe.
+#[derive(Clone, Debug)]
+pub struct ChangeEvent {
+    pub key: String,
+    pub seq: u64,
+}
+
+/// Broadcasts every `insert`/`delete` as a `ChangeEvent`, split per shard so
+/// a `WATCH <key>` only wakes for changes on that key's shard, plus one
+/// database-wide channel for `WATCH_QUERY`, which can't know in advance
+/// which shards a query's matches live on.
+pub struct ChangeNotifier {
+    shard_senders: HashMap<u32, broadcast::Sender<ChangeEvent>>,
+    global_sender: broadcast::Sender<ChangeEvent>,
+    seq: AtomicU64,
+}
+
+impl ChangeNotifier {
+    pub fn new(shard_ids: Vec<u32>) -> Self {
+        let mut shard_senders = HashMap::new();
+        for shard_id in shard_ids {
+            let (tx, _rx) = broadcast::channel(256);
+            shard_senders.insert(shard_id, tx);
+        }
+        let (global_sender, _rx) = broadcast::channel(256);
+
+        ChangeNotifier {
+            shard_senders,
+            global_sender,
+            seq: AtomicU64::new(0),
+        }
+    }
+
+    /// The most recent sequence number handed out by `notify`, or `0` if
+    /// nothing has changed yet.
+    pub fn current_seq(&self) -> u64 {
+        self.seq.load(Ordering::SeqCst)
+    }
+
+    /// Records a change to `key` on `shard_id` and wakes any subscribers.
+    /// Returns the event's sequence number. Send errors are ignored -- a
+    /// channel with no current receivers just means nobody is watching.
+    pub fn notify(&self, shard_id: u32, key: String) -> u64 {
+        let seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        let event = ChangeEvent { key, seq };
+
+        if let Some(tx) = self.shard_senders.get(&shard_id) {
+            let _ = tx.send(event.clone());
+        }
+        let _ = self.global_sender.send(event);
+
+        seq
+    }
+
+    pub fn subscribe_shard(&self, shard_id: u32) -> Option<broadcast::Receiver<ChangeEvent>> {
+        self.shard_senders.get(&shard_id).map(|tx| tx.subscribe())
+    }
+
+    pub fn subscribe_all(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.global_sender.subscribe()
+    }
+}
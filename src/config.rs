@@ -8,6 +8,13 @@ use std::error::Error;
 pub enum IndexType {
     Numeric,
     String,
+    /// Inverted-index text search with tokenization, typo-tolerant fuzzy
+    /// matching, and ranked results, driven by the `MATCH`/`SEARCH` operators.
+    FullText,
+    /// Morton-coded spatial index over a coordinate field (`[lon, lat]` or
+    /// an object with `lon`/`lat`), driven by the `WITHIN_BBOX`/`NEAR`
+    /// operators.
+    Geo,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -15,13 +22,39 @@ pub enum IndexType {
 ///
 /// # Fields
 ///
-/// * `data_dir` - A string representing the directory where data will be stored.
+/// * `data_dir` - Where shard data is stored. A bare path or `file://<path>` selects the
+///   local filesystem backend; `memory://` selects the in-memory backend, `lmdb://<path>`
+///   the memory-mapped backend and `sqlite://<path>` the SQLite backend (see
+///   `storage::backend`). The `CONVERT` command migrates a running instance between
+///   backends without a restart.
 /// * `num_shards` - An unsigned 32-bit integer specifying the number of shards to use.
 /// * `indexed_fields` - A vector of `IndexedField` structs representing the fields that will be indexed.
+/// * `replication` - Optional Raft replication settings for running this node as part of a cluster.
+/// * `checkpoint_interval_secs` - How often the background task checkpoints each shard's WAL, in
+///   seconds. `None` disables scheduled checkpointing (shards are still checkpointed on demand via
+///   the `CHECKPOINT` command).
 pub struct Config {
-    pub data_dir: String,           
-    pub num_shards: u32,            
-    pub indexed_fields: Vec<IndexedField>, 
+    pub data_dir: String,
+    pub num_shards: u32,
+    pub indexed_fields: Vec<IndexedField>,
+    #[serde(default)]
+    pub replication: Option<ReplicationConfig>,
+    #[serde(default)]
+    pub checkpoint_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// Settings needed to run this node as part of a Raft-replicated cluster.
+///
+/// # Fields
+///
+/// * `node_id` - Unique identifier for this node within the cluster.
+/// * `listen_addr` - Address this node accepts `RAFT_APPEND` traffic on.
+/// * `peers` - Addresses of the other nodes in the cluster.
+pub struct ReplicationConfig {
+    pub node_id: u32,
+    pub listen_addr: String,
+    pub peers: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -0,0 +1,43 @@
+use crate::config::Config;
+use crate::hyperion_db::HyperionDB;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Polls `config_path` for changes and, whenever its contents change,
+/// applies the new `indexed_fields`/`checkpoint_interval_secs` to the
+/// running instance via `HyperionDB::reload_config` -- without dropping
+/// connections or restarting the TCP server. The `RELOAD` command triggers
+/// the same reload on demand instead of waiting for the next poll.
+pub async fn watch(db: Arc<HyperionDB>, config_path: String) {
+    let mut last_contents = tokio::fs::read_to_string(&config_path).await.ok();
+
+    loop {
+        sleep(POLL_INTERVAL).await;
+
+        let contents = match tokio::fs::read_to_string(&config_path).await {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        if Some(&contents) == last_contents.as_ref() {
+            continue;
+        }
+
+        match serde_json::from_str::<Config>(&contents) {
+            Ok(new_config) => match db.reload_config(new_config).await {
+                Ok(report) => {
+                    println!(
+                        "Config reload: +{:?} fields, -{:?} fields, checkpoint_interval_secs={:?}",
+                        report.added_fields, report.removed_fields, report.checkpoint_interval_secs
+                    );
+                    last_contents = Some(contents);
+                }
+                Err(e) => eprintln!("Config reload failed: {}", e),
+            },
+            Err(e) => eprintln!("Config reload: invalid config file: {}", e),
+        }
+    }
+}
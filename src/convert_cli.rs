@@ -0,0 +1,56 @@
+// src/convert_cli.rs
+//
+// Standalone CLI companion to the `CONVERT` admin command: migrates shard
+// files between storage backends while the server is stopped, for
+// operators who would rather not bring an instance up against the old
+// backend just to issue one command. `CONVERT` is the live equivalent and
+// additionally rebuilds in-memory indices; this tool only moves bytes.
+//
+// Usage: convert_cli <source_uri> <dest_uri> <num_shards>
+//   e.g.: convert_cli file://./hyperiondb_data sqlite://./hyperiondb.sqlite3 4
+
+mod storage;
+
+use std::env;
+use std::error::Error;
+use std::process;
+use std::sync::Arc;
+
+use storage::backend_from_uri;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    let (source_uri, dest_uri, num_shards) = match args.as_slice() {
+        [_, source, dest, num_shards] => {
+            let num_shards: u32 = num_shards.parse().unwrap_or_else(|_| {
+                eprintln!("Invalid num_shards: {}", num_shards);
+                process::exit(1);
+            });
+            (source.clone(), dest.clone(), num_shards)
+        }
+        _ => {
+            eprintln!("Usage: convert_cli <source_uri> <dest_uri> <num_shards>");
+            process::exit(1);
+        }
+    };
+
+    let source = backend_from_uri(&source_uri);
+    let dest = backend_from_uri(&dest_uri);
+
+    let mut records_migrated = 0usize;
+    for shard_id in 0..num_shards {
+        let shard_data = source.load_shard(shard_id).await?;
+        let shard = Arc::new(dashmap::DashMap::from_iter(shard_data.into_iter()));
+        let shard_record_count = shard.len();
+        dest.save_shard(shard_id, shard).await?;
+        records_migrated += shard_record_count;
+        println!("Shard {}: migrated {} records", shard_id, shard_record_count);
+    }
+
+    println!(
+        "Done: {} shards, {} records migrated from {} to {}",
+        num_shards, records_migrated, source_uri, dest_uri
+    );
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+
+/// The database's public error type. Every variant carries a stable,
+/// machine-readable `code()` (for clients that want to branch on failure
+/// kind without parsing a message) alongside a human-readable `Display`,
+/// replacing the `Box<dyn Error>`/string-literal errors the public API used
+/// to return, which callers could only ever log or show to a human.
+#[derive(Debug)]
+pub enum HyperionError {
+    /// `GET`/`DELETE`/etc. addressed a key with no current record.
+    KeyNotFound(String),
+    /// A command referenced a field with no configured index.
+    IndexNotFound(String),
+    /// A `QUERY`/`QUERY_EXPR` condition used an operator that no index
+    /// type supports at all (e.g. `LIKE`).
+    InvalidQueryOperator { operator: String, field: String },
+    /// A `QUERY`/`QUERY_EXPR` condition's operator is valid in general but
+    /// doesn't match the field's actual index type (e.g. `CONTAINS` against
+    /// a `Numeric` index, or `>` against a `FullText` index).
+    TypeMismatch { field: String, expected: &'static str },
+    /// A value failed to (de)serialize, e.g. malformed JSON in a request.
+    Serialization(String),
+    /// A filesystem or storage-backend operation failed.
+    Io(String),
+    /// A checkpoint or WAL file on disk was unreadable or inconsistent.
+    CorruptCheckpoint(String),
+}
+
+impl HyperionError {
+    /// A stable string identifying the failure kind, independent of the
+    /// human-readable message, for clients that branch on error type.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HyperionError::KeyNotFound(_) => "KEY_NOT_FOUND",
+            HyperionError::IndexNotFound(_) => "INDEX_NOT_FOUND",
+            HyperionError::InvalidQueryOperator { .. } => "INVALID_QUERY_OPERATOR",
+            HyperionError::TypeMismatch { .. } => "TYPE_MISMATCH",
+            HyperionError::Serialization(_) => "SERIALIZATION",
+            HyperionError::Io(_) => "IO",
+            HyperionError::CorruptCheckpoint(_) => "CORRUPT_CHECKPOINT",
+        }
+    }
+}
+
+impl fmt::Display for HyperionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HyperionError::KeyNotFound(key) => write!(f, "key not found: {key}"),
+            HyperionError::IndexNotFound(field) => write!(f, "no index configured for field: {field}"),
+            HyperionError::InvalidQueryOperator { operator, field } => {
+                write!(f, "operator {operator} is not supported on field {field}")
+            }
+            HyperionError::TypeMismatch { field, expected } => {
+                write!(f, "field {field} is not a {expected} index")
+            }
+            HyperionError::Serialization(message) => write!(f, "serialization error: {message}"),
+            HyperionError::Io(message) => write!(f, "storage error: {message}"),
+            HyperionError::CorruptCheckpoint(message) => write!(f, "corrupt checkpoint: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for HyperionError {}
+
+impl From<std::io::Error> for HyperionError {
+    fn from(err: std::io::Error) -> Self {
+        HyperionError::Io(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for HyperionError {
+    fn from(err: serde_json::Error) -> Self {
+        HyperionError::Serialization(err.to_string())
+    }
+}
+
+/// The storage layer (`storage`/`shard_manager`) still returns a boxed
+/// error internally, since its failure modes (WAL/file IO, backend-specific
+/// errors) are an implementation detail rather than part of the public API
+/// contract this type describes. This is the boundary where those get
+/// folded into the one `Io` bucket a caller of `HyperionDB`'s public
+/// methods can match on.
+impl From<Box<dyn std::error::Error + Send + Sync>> for HyperionError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        HyperionError::Io(err.to_string())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::error::HyperionError;
+use crate::handler::handle_command;
+use crate::hyperion_db::HyperionDB;
+use crate::metrics::render_prometheus;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// Starts a minimal HTTP/JSON REST gateway in front of `db`, alongside the
+/// existing line-oriented TCP server. Every route is served by building the
+/// equivalent line-protocol command and running it through the same
+/// `handle_command` the TCP/WS front ends use, so a command added to one
+/// dispatcher (BATCH, SEARCH, STATS, WATCH, ...) never silently goes missing
+/// from the other.
+///
+/// Routes:
+/// * `POST   /records/:key` - insert/update a record, JSON body is the value
+/// * `GET    /records/:key` - fetch a record, 404 if it doesn't exist
+/// * `DELETE /records/:key` - delete a record, 404 if it doesn't exist
+/// * `GET    /records`      - list every record
+/// * `POST   /records`      - batch insert/update, JSON body `{"items": [[key, value], ...]}`
+/// * `POST   /records/delete` - batch delete, JSON body `{"keys": [key, ...]}`
+/// * `POST   /query`        - evaluate a `QUERY` expression, JSON body `{"query": "..."}`
+/// * `GET    /metrics`      - Prometheus exposition format, including per-shard record counts
+pub async fn serve_http(db: Arc<HyperionDB>, addr: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let listener = TcpListener::bind(addr).await?;
+    println!("HyperionDB HTTP gateway running on {}", addr);
+
+    loop {
+        let (socket, _) = listener.accept().await?;
+        let db = db.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(socket, db).await {
+                eprintln!("HTTP gateway connection error: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut socket: tokio::net::TcpStream,
+    db: Arc<HyperionDB>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let request = read_request(&mut socket).await?;
+
+    if request.method == "GET" && request.path.trim_matches('/') == "metrics" {
+        let body = render_prometheus(&db.metrics, &db.shard_sizes());
+        return write_text_response(&mut socket, 200, &body).await;
+    }
+
+    let (status, body) = route(&db, &request).await;
+    write_response(&mut socket, status, &body).await
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> Result<Request, Box<dyn Error + Send + Sync>> {
+    let mut reader = BufReader::new(socket);
+    let mut raw = Vec::new();
+
+    // Read headers until the blank line that separates them from the body.
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte).await?;
+        raw.push(byte[0]);
+        if raw.ends_with(b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    let header_text = String::from_utf8_lossy(&raw).to_string();
+    let mut lines = header_text.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("GET").to_string();
+    let path = parts.next().unwrap_or("/").to_string();
+
+    let content_length: usize = lines
+        .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let mut body = vec![0u8; content_length];
+    if content_length > 0 {
+        reader.read_exact(&mut body).await?;
+    }
+
+    Ok(Request {
+        method,
+        path,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+async fn route(db: &HyperionDB, request: &Request) -> (u16, Value) {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+    let command = match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["records"]) => "LIST".to_string(),
+        ("POST", ["records"]) => match serde_json::from_str::<Value>(&request.body) {
+            Ok(body) => match body.get("items") {
+                Some(items) => format!("INSERT_OR_UPDATE_MANY {}", items),
+                None => return (400, json!({ "error": "missing \"items\" field" })),
+            },
+            Err(e) => return (400, json!({ "error": format!("invalid JSON body: {}", e) })),
+        },
+        // Matched ahead of the generic `/records/:key` arms below, since
+        // `["records", "delete"]` would otherwise be read as a key literally
+        // named "delete".
+        ("POST", ["records", "delete"]) => match serde_json::from_str::<Value>(&request.body) {
+            Ok(body) => match body.get("keys") {
+                Some(keys) => format!("DELETE_MANY {}", keys),
+                None => return (400, json!({ "error": "missing \"keys\" field" })),
+            },
+            Err(e) => return (400, json!({ "error": format!("invalid JSON body: {}", e) })),
+        },
+        ("GET", ["records", key]) => format!("GET {}", key),
+        ("DELETE", ["records", key]) => format!("DELETE {}", key),
+        ("POST", ["records", key]) => format!("INSERT_OR_UPDATE {} {}", key, request.body.trim()),
+        ("POST", ["query"]) => match serde_json::from_str::<Value>(&request.body) {
+            Ok(body) => match body.get("query").and_then(Value::as_str) {
+                Some(query_str) => format!("QUERY {}", query_str),
+                None => return (400, json!({ "error": "missing \"query\" field" })),
+            },
+            Err(e) => return (400, json!({ "error": format!("invalid JSON body: {}", e) })),
+        },
+        _ => return (404, json!({ "error": "unknown route" })),
+    };
+
+    dispatch(db, command).await
+}
+
+/// Runs `command` through the shared TCP/WS command dispatcher and turns its
+/// response into an HTTP status + JSON body. This is what keeps the HTTP
+/// surface from drifting out of sync with the line protocol: any command
+/// `handle_command` knows (BATCH, SEARCH, STATS, WATCH, ...) is reachable
+/// here for free, not just the handful `route` names explicitly.
+async fn dispatch(db: &HyperionDB, command: String) -> (u16, Value) {
+    match handle_command(db, command).await {
+        Ok(response) => response_to_json(response.trim_end()),
+        Err(e) => {
+            let status = match e.downcast_ref::<HyperionError>() {
+                Some(HyperionError::KeyNotFound(_)) => 404,
+                _ => 400,
+            };
+            (status, json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Translates a line-protocol response into an HTTP status + JSON body.
+/// Some commands format their own usage errors as `Ok("ERR ...")` rather
+/// than `Err` (there's no status code on a raw socket to carry that
+/// distinction), so those are treated the same as a dispatch error here.
+fn response_to_json(response: &str) -> (u16, Value) {
+    if let Some(message) = response.strip_prefix("ERR ") {
+        return (400, json!({ "error": message }));
+    }
+    match response {
+        "NULL" => (404, json!({ "error": "not found" })),
+        "OK" => (200, json!({ "status": "ok" })),
+        _ => match serde_json::from_str::<Value>(response) {
+            Ok(value) => (200, value),
+            Err(_) => (200, json!(response)),
+        },
+    }
+}
+
+async fn write_response(
+    socket: &mut tokio::net::TcpStream,
+    status: u16,
+    body: &Value,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let body = serde_json::to_string(body)?;
+    write_raw_response(socket, status, "application/json", &body).await
+}
+
+async fn write_text_response(
+    socket: &mut tokio::net::TcpStream,
+    status: u16,
+    body: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    write_raw_response(socket, status, "text/plain; version=0.0.4", body).await
+}
+
+async fn write_raw_response(
+    socket: &mut tokio::net::TcpStream,
+    status: u16,
+    content_type: &str,
+    body: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    );
+    socket.write_all(response.as_bytes()).await?;
+    Ok(())
+}
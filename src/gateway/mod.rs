@@ -0,0 +1,5 @@
+pub mod http;
+pub mod websocket;
+
+pub use http::serve_http;
+pub use websocket::{perform_handshake, read_text_frame, serve_ws, write_text_frame};
@@ -0,0 +1,151 @@
+use crate::handler::handle_command;
+use crate::hyperion_db::HyperionDB;
+use base64::Engine;
+use sha1::{Digest, Sha1};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Starts a WebSocket listener in front of `db`'s command protocol, so
+/// browser and tunneled clients that can't open a raw TCP socket can still
+/// drive `INSERT`/`QUERY`/... by sending each command as a text frame and
+/// reading the response back the same way, pipelined over one connection
+/// just like the existing TCP loop. Performs the HTTP Upgrade handshake
+/// itself (no axum/tungstenite dependency), consistent with the hand-rolled
+/// HTTP gateway in `gateway::http`.
+pub async fn serve_ws(db: Arc<HyperionDB>, addr: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let listener = TcpListener::bind(addr).await?;
+    println!("HyperionDB WebSocket gateway running on {}", addr);
+
+    loop {
+        let (socket, _) = listener.accept().await?;
+        let db = db.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(socket, db).await {
+                eprintln!("WebSocket gateway connection error: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut socket: TcpStream,
+    db: Arc<HyperionDB>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    perform_handshake(&mut socket).await?;
+
+    while let Some(command) = read_text_frame(&mut socket).await? {
+        let response = match handle_command(&db, command).await {
+            Ok(resp) => resp,
+            Err(e) => format!("ERR {}\n", e),
+        };
+
+        write_text_frame(&mut socket, response.trim_end_matches('\n')).await?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn perform_handshake(socket: &mut TcpStream) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut header_text = String::new();
+
+    {
+        let mut reader = BufReader::new(&mut *socket);
+        loop {
+            let mut line = String::new();
+            let bytes_read = reader.read_line(&mut line).await?;
+            if bytes_read == 0 || line == "\r\n" {
+                break;
+            }
+            header_text.push_str(&line);
+        }
+    }
+
+    let key = header_text
+        .lines()
+        .find(|line| line.to_lowercase().starts_with("sec-websocket-key:"))
+        .and_then(|line| line.splitn(2, ':').nth(1))
+        .map(|v| v.trim().to_string())
+        .ok_or("Missing Sec-WebSocket-Key header")?;
+
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(WS_GUID.as_bytes());
+    let accept = base64::engine::general_purpose::STANDARD.encode(hasher.finalize());
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    );
+    socket.write_all(response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Reads one WebSocket text frame, unmasking the client-sent payload per
+/// RFC 6455. Returns `None` on a close frame or disconnect.
+pub(crate) async fn read_text_frame(socket: &mut TcpStream) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+    let mut header = [0u8; 2];
+    if socket.read_exact(&mut header).await.is_err() {
+        return Ok(None);
+    }
+
+    let opcode = header[0] & 0x0F;
+    if opcode == 0x8 {
+        return Ok(None);
+    }
+
+    let masked = header[1] & 0x80 != 0;
+    let mut len = (header[1] & 0x7F) as u64;
+
+    if len == 126 {
+        let mut ext = [0u8; 2];
+        socket.read_exact(&mut ext).await?;
+        len = u16::from_be_bytes(ext) as u64;
+    } else if len == 127 {
+        let mut ext = [0u8; 8];
+        socket.read_exact(&mut ext).await?;
+        len = u64::from_be_bytes(ext);
+    }
+
+    let mut mask_key = [0u8; 4];
+    if masked {
+        socket.read_exact(&mut mask_key).await?;
+    }
+
+    let mut payload = vec![0u8; len as usize];
+    socket.read_exact(&mut payload).await?;
+
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask_key[i % 4];
+        }
+    }
+
+    Ok(Some(String::from_utf8_lossy(&payload).trim().to_string()))
+}
+
+/// Writes one unmasked WebSocket text frame, as required for server-to-client frames.
+pub(crate) async fn write_text_frame(socket: &mut TcpStream, text: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let payload = text.as_bytes();
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x81);
+
+    if payload.len() < 126 {
+        frame.push(payload.len() as u8);
+    } else if payload.len() <= u16::MAX as usize {
+        frame.push(126);
+        frame.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+    }
+
+    frame.extend_from_slice(payload);
+    socket.write_all(&frame).await?;
+    Ok(())
+}
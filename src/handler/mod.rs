@@ -13,9 +13,27 @@ pub enum Expr {
     Condition(Condition),
     And(Box<Expr>, Box<Expr>),
     Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
     Group(Box<Expr>),
 }
 
+/// Pagination requested via trailing `LIMIT`/`OFFSET` clauses on a `QUERY`.
+pub struct Pagination {
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+/// Full parse of a `QUERY`-style request: the boolean predicate, plus the
+/// trailing `ORDER BY`/`LIMIT`/`OFFSET` clauses applied to its results.
+/// `order_by` is the sorted field together with whether it's descending
+/// (`false` means ascending, including when no direction was given).
+pub struct QueryPlan {
+    pub expr: Expr,
+    pub order_by: Option<(String, bool)>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
 fn tokenize(s: &str) -> Vec<String> {
     let mut tokens = Vec::new();
     let mut chars = s.chars().peekable();
@@ -36,10 +54,13 @@ fn tokenize(s: &str) -> Vec<String> {
                 }
             }
             tokens.push(token);
+        } else if c == ',' || c == '(' || c == ')' {
+            chars.next();
+            tokens.push(c.to_string());
         } else {
             let mut token = String::new();
             while let Some(&ch) = chars.peek() {
-                if ch.is_whitespace() {
+                if ch.is_whitespace() || ch == ',' || ch == '(' || ch == ')' {
                     break;
                 } else {
                     token.push(ch);
@@ -49,6 +70,15 @@ fn tokenize(s: &str) -> Vec<String> {
             let upper_token = token.to_uppercase();
             if upper_token == "AND"
                 || upper_token == "OR"
+                || upper_token == "NOT"
+                || upper_token == "IN"
+                || upper_token == "BETWEEN"
+                || upper_token == "ORDER"
+                || upper_token == "BY"
+                || upper_token == "ASC"
+                || upper_token == "DESC"
+                || upper_token == "LIMIT"
+                || upper_token == "OFFSET"
                 || upper_token == "("
                 || upper_token == ")"
             {
@@ -81,11 +111,11 @@ fn parse_or(tokens: &[String], i: &mut usize) -> Result<(Expr, usize), String> {
 }
 
 fn parse_and(tokens: &[String], i: &mut usize) -> Result<(Expr, usize), String> {
-    let (mut left, _) = parse_term(tokens, i)?;
+    let (mut left, _) = parse_not(tokens, i)?;
     while let Some(token) = tokens.get(*i) {
         if token.to_uppercase() == "AND" {
             *i += 1;
-            let (right, _) = parse_term(tokens, i)?;
+            let (right, _) = parse_not(tokens, i)?;
             left = Expr::And(Box::new(left), Box::new(right));
         } else {
             break;
@@ -94,11 +124,25 @@ fn parse_and(tokens: &[String], i: &mut usize) -> Result<(Expr, usize), String>
     Ok((left, *i))
 }
 
+fn parse_not(tokens: &[String], i: &mut usize) -> Result<(Expr, usize), String> {
+    if tokens.get(*i).map(|t| t.as_str()) == Some("NOT") {
+        *i += 1;
+        let (inner, _) = parse_term(tokens, i)?;
+        Ok((Expr::Not(Box::new(inner)), *i))
+    } else {
+        parse_term(tokens, i)
+    }
+}
+
 fn parse_term(tokens: &[String], i: &mut usize) -> Result<(Expr, usize), String> {
     if let Some(token) = tokens.get(*i) {
         if token == "(" {
             *i += 1;
-            let (expr, _) = parse_expression(tokens)?;
+            // Continue from the shared cursor `i`, not a fresh one at 0 --
+            // `parse_expression` always starts over from the beginning of
+            // `tokens`, which would re-parse everything before this `(`
+            // and recurse into it again forever.
+            let (expr, _) = parse_or(tokens, i)?;
             if tokens.get(*i) == Some(&")".to_string()) {
                 *i += 1;
                 Ok((Expr::Group(Box::new(expr)), *i))
@@ -119,15 +163,214 @@ fn parse_condition(tokens: &[String], i: &mut usize) -> Result<Condition, String
     *i += 1;
     let operator = tokens.get(*i).ok_or("Falta el operador en la condición")?;
     *i += 1;
-    let value = tokens.get(*i).ok_or("Falta el valor en la condición")?;
-    *i += 1;
+
+    // `IN` takes a parenthesized, comma-separated list of values instead of
+    // a single value token, e.g. `status IN (active, pending)`.
+    let value = if operator == "IN" {
+        if tokens.get(*i).map(|t| t.as_str()) != Some("(") {
+            return Err("Se esperaba '(' después de IN".to_string());
+        }
+        *i += 1;
+        let mut values = Vec::new();
+        while let Some(token) = tokens.get(*i) {
+            if token == ")" {
+                *i += 1;
+                break;
+            }
+            if token != "," {
+                values.push(token.clone());
+            }
+            *i += 1;
+        }
+        values.join(",")
+    } else if operator == "BETWEEN" {
+        // `BETWEEN lo AND hi` -- the `AND` keyword is required and consumed
+        // here, but the two bounds are still joined with a space (not
+        // stored with `AND` in between) to match `query_string`'s and
+        // `query_numeric`'s `"lo hi"` parsing.
+        let lo = tokens.get(*i).ok_or("Falta el límite inferior de BETWEEN")?.clone();
+        *i += 1;
+        if tokens.get(*i).map(|t| t.as_str()) != Some("AND") {
+            return Err("Se esperaba 'AND' en BETWEEN lo AND hi".to_string());
+        }
+        *i += 1;
+        let hi = tokens.get(*i).ok_or("Falta el límite superior de BETWEEN")?.clone();
+        *i += 1;
+        format!("{} {}", lo, hi)
+    } else {
+        let value = tokens.get(*i).ok_or("Falta el valor en la condición")?.clone();
+        *i += 1;
+        value
+    };
 
     Ok(Condition {
         field: field.clone(),
         operator: operator.clone(),
-        value: value.clone(),
+        value,
     })
 }
+
+/// Parses an optional `ORDER BY <field> [ASC|DESC]` clause following a query
+/// expression, ahead of any `LIMIT`/`OFFSET`. Ascending when no direction is
+/// given.
+fn parse_order_by(tokens: &[String], i: &mut usize) -> Result<Option<(String, bool)>, String> {
+    if tokens.get(*i).map(|t| t.as_str()) != Some("ORDER") {
+        return Ok(None);
+    }
+    *i += 1;
+    if tokens.get(*i).map(|t| t.as_str()) != Some("BY") {
+        return Err("Se esperaba 'BY' después de ORDER".to_string());
+    }
+    *i += 1;
+    let field = tokens.get(*i).ok_or("Falta el campo de ORDER BY")?.clone();
+    *i += 1;
+    let descending = match tokens.get(*i).map(|t| t.as_str()) {
+        Some("DESC") => {
+            *i += 1;
+            true
+        }
+        Some("ASC") => {
+            *i += 1;
+            false
+        }
+        _ => false,
+    };
+    Ok(Some((field, descending)))
+}
+
+/// Sorts `results` in place by the value of `field` (dotted paths resolved
+/// the same way indexing does), reversing the order when `descending`.
+/// Records missing the field sort after every record that has it.
+fn sort_by_field(results: &mut [Value], field: &str, descending: bool) {
+    results.sort_by(|a, b| {
+        match (field_value(a, field), field_value(b, field)) {
+            (Some(a), Some(b)) => {
+                let ordering = compare_values(a, b);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }
+    });
+}
+
+fn field_value<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => crate::index::get_nested_field(map, field),
+        _ => None,
+    }
+}
+
+fn compare_values(a: &Value, b: &Value) -> std::cmp::Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .and_then(|a| b.as_f64().map(|b| a.partial_cmp(&b)))
+            .flatten()
+            .unwrap_or(std::cmp::Ordering::Equal),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+/// Parses trailing `LIMIT n` / `OFFSET n` clauses following a query
+/// expression. Either, both, or neither may be present, in any order.
+fn parse_pagination(tokens: &[String], i: &mut usize) -> Result<Pagination, String> {
+    let mut limit = None;
+    let mut offset = 0;
+
+    while let Some(token) = tokens.get(*i) {
+        match token.as_str() {
+            "LIMIT" => {
+                *i += 1;
+                let n = tokens.get(*i).ok_or("Falta el valor de LIMIT")?;
+                limit = Some(n.parse::<usize>().map_err(|e| e.to_string())?);
+                *i += 1;
+            }
+            "OFFSET" => {
+                *i += 1;
+                let n = tokens.get(*i).ok_or("Falta el valor de OFFSET")?;
+                offset = n.parse::<usize>().map_err(|e| e.to_string())?;
+                *i += 1;
+            }
+            _ => return Err(format!("Token inesperado: {}", token)),
+        }
+    }
+
+    Ok(Pagination { limit, offset })
+}
+
+/// Applies a parsed `Pagination` clause to a result set.
+fn paginate(mut results: Vec<Value>, pagination: &Pagination) -> Vec<Value> {
+    if pagination.offset > 0 {
+        if pagination.offset >= results.len() {
+            return Vec::new();
+        }
+        results.drain(..pagination.offset);
+    }
+    if let Some(limit) = pagination.limit {
+        results.truncate(limit);
+    }
+    results
+}
+/// Parses a full `QUERY`-style request (without the leading `QUERY`
+/// keyword): the boolean expression, then an optional `ORDER BY`, then an
+/// optional `LIMIT`/`OFFSET`.
+fn parse_query_plan(tokens: &[String]) -> Result<QueryPlan, String> {
+    let (expr, mut i) = parse_expression(tokens)?;
+    let order_by = parse_order_by(tokens, &mut i)?;
+    let pagination = parse_pagination(tokens, &mut i)?;
+    Ok(QueryPlan {
+        expr,
+        order_by,
+        limit: pagination.limit,
+        offset: if pagination.offset > 0 { Some(pagination.offset) } else { None },
+    })
+}
+
+/// Applies a parsed `QueryPlan`'s `ORDER BY` and pagination clauses to a
+/// result set, in that order -- sorting before paginating so `LIMIT`/
+/// `OFFSET` slice the sorted list rather than whatever order the indices
+/// happened to return.
+fn apply_query_plan(mut results: Vec<Value>, plan: &QueryPlan) -> Vec<Value> {
+    if let Some((field, descending)) = &plan.order_by {
+        sort_by_field(&mut results, field, *descending);
+    }
+    paginate(
+        results,
+        &Pagination {
+            limit: plan.limit,
+            offset: plan.offset.unwrap_or(0),
+        },
+    )
+}
+
+/// Parses and evaluates a `QUERY`-style expression string (without the
+/// leading `QUERY` keyword), applying any trailing `ORDER BY`/pagination
+/// clause -- the same steps the `QUERY` arm of `handle_command` runs, but
+/// returning the matching records instead of a response line.
+pub async fn evaluate_query_string(db: &HyperionDB, query_str: &str) -> Result<Vec<Value>, String> {
+    let tokens = tokenize(query_str.trim());
+    let plan = parse_query_plan(&tokens)?;
+    let results = db.query_expression(&plan.expr).await.map_err(|e| e.to_string())?;
+    Ok(apply_query_plan(results, &plan))
+}
+
+/// Whether `command` is one of the writes replicated through Raft
+/// (`INSERT`/`INSERT_OR_UPDATE`/`DELETE`) rather than applied locally.
+/// Used by the TCP front end in `main.rs` to decide whether a command
+/// needs `RaftNode::propose` (log + quorum + redirect-if-follower) or can
+/// go straight to `handle_command`.
+pub fn is_replicated_write(command: &str) -> bool {
+    let verb = command.trim().splitn(2, ' ').next().unwrap_or("").to_uppercase();
+    matches!(verb.as_str(), "INSERT" | "INSERT_OR_UPDATE" | "DELETE")
+}
+
 pub async fn handle_command(db: &HyperionDB, command: String) -> Result<String, Box<dyn Error>> {
     let cmd_line = command.trim();
     let cmd_parts: Vec<&str> = cmd_line.splitn(2, ' ').collect();
@@ -178,10 +421,14 @@ pub async fn handle_command(db: &HyperionDB, command: String) -> Result<String,
             if let Some(rest) = cmd_parts.get(1) {
                 let rest = rest.trim();
                 let tokens = tokenize(rest);
-                match parse_expression(&tokens) {
-                    Ok((expr, _)) => {
+                match parse_query_plan(&tokens) {
+                    Ok(plan) => {
                         // Ejecutamos la consulta con la expresión lógica
-                        let results = db.query_expression(&expr).await;
+                        let results = match db.query_expression(&plan.expr).await {
+                            Ok(results) => results,
+                            Err(e) => return Ok(format!("ERR [{}] {}\n", e.code(), e)),
+                        };
+                        let results = apply_query_plan(results, &plan);
                         Ok(format!("{}\n", serde_json::to_string(&results)?))
                     },
                     Err(err) => Ok(format!("ERR {}\n", err)),
@@ -228,6 +475,479 @@ pub async fn handle_command(db: &HyperionDB, command: String) -> Result<String,
                 Ok("ERR Usage: DELETE_MANY <[key1, key2, ...]>\n".to_string())
             }
         }
+        "QUERY_BATCH" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let conditions: Vec<(String, String, String)> = serde_json::from_str(rest)?;
+                let results = db.query_batch(&conditions).await;
+                Ok(format!("{}\n", serde_json::to_string(&results)?))
+            } else {
+                Ok("ERR Usage: QUERY_BATCH <[[field, operator, value], ...]>\n".to_string())
+            }
+        }
+        "UPGRADE" => {
+            if let Some(shard_id_str) = cmd_parts.get(1) {
+                let shard_id: u32 = shard_id_str.trim().parse()?;
+                match db.upgrade_shard(shard_id).await? {
+                    Some(from_version) => Ok(format!("UPGRADED from v{}\n", from_version)),
+                    None => Ok("ALREADY_CURRENT\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: UPGRADE <shard_id>\n".to_string())
+            }
+        }
+        "INSERT_CHUNKED" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let insert_parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(key), Some(value_str)) = (insert_parts.get(0), insert_parts.get(1)) {
+                    let value: Value = serde_json::from_str(value_str)?;
+                    db.insert_chunked(key.to_string(), value).await?;
+                    Ok("OK\n".to_string())
+                } else {
+                    Ok("ERR Usage: INSERT_CHUNKED <key> <value>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: INSERT_CHUNKED <key> <value>\n".to_string())
+            }
+        }
+        "GET_CHUNKED" => {
+            if let Some(key) = cmd_parts.get(1) {
+                match db.get_chunked(key).await {
+                    Some(value) => Ok(format!("{}\n", value)),
+                    None => Ok("NULL\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: GET_CHUNKED <key>\n".to_string())
+            }
+        }
+        "LWW_INSERT" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(key), Some(value_str)) = (parts.get(0), parts.get(1)) {
+                    let value: Value = serde_json::from_str(value_str)?;
+                    // The stamp's timestamp comes from this node's HybridClock,
+                    // not the request -- a client-supplied one would let a
+                    // write pin a key forever by claiming an arbitrary future
+                    // timestamp.
+                    let applied = db.insert_lww(key.to_string(), value).await?;
+                    Ok(format!("{}\n", if applied { "APPLIED" } else { "IGNORED" }))
+                } else {
+                    Ok("ERR Usage: LWW_INSERT <key> <value>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: LWW_INSERT <key> <value>\n".to_string())
+            }
+        }
+        "MERKLE_ROOT" => {
+            if let Some(shard_id_str) = cmd_parts.get(1) {
+                let shard_id: u32 = shard_id_str.trim().parse()?;
+                match db.merkle_root(shard_id) {
+                    Some(root) => Ok(format!("{}\n", root)),
+                    None => Ok("ERR Unknown shard\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: MERKLE_ROOT <shard_id>\n".to_string())
+            }
+        }
+        "MERKLE_DIFF" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let diff_parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(shard_id_str), Some(leaves_json)) = (diff_parts.get(0), diff_parts.get(1)) {
+                    let shard_id: u32 = shard_id_str.parse()?;
+                    let remote_leaves: std::collections::HashMap<String, String> =
+                        serde_json::from_str(leaves_json)?;
+                    let divergent = db.merkle_diff(shard_id, &remote_leaves);
+                    Ok(format!("{}\n", serde_json::to_string(&divergent)?))
+                } else {
+                    Ok("ERR Usage: MERKLE_DIFF <shard_id> <leaves_json>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: MERKLE_DIFF <shard_id> <leaves_json>\n".to_string())
+            }
+        }
+        "GET_CAUSAL" => {
+            if let Some(key) = cmd_parts.get(1) {
+                match db.get(key).await {
+                    Some(value) => {
+                        let token = db.version_for(key);
+                        Ok(format!("{}\n", serde_json::to_string(&serde_json::json!({ "value": value, "token": token }))?))
+                    }
+                    None => Ok("NULL\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: GET_CAUSAL <key>\n".to_string())
+            }
+        }
+        "INSERT_IF_MATCH" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(3, ' ').collect();
+                if let (Some(key), Some(token_str), Some(value_str)) =
+                    (parts.get(0), parts.get(1), parts.get(2))
+                {
+                    let token: crate::versioning::VersionVector = serde_json::from_str(token_str)?;
+                    let value: Value = serde_json::from_str(value_str)?;
+                    match db.insert_if_match(key.to_string(), token, value).await? {
+                        crate::hyperion_db::insert_if_match::CausalWriteResult::Applied(new_token) => Ok(format!(
+                            "{}\n",
+                            serde_json::to_string(&serde_json::json!({ "status": "applied", "token": new_token }))?
+                        )),
+                        crate::hyperion_db::insert_if_match::CausalWriteResult::Conflict {
+                            stored_value,
+                            stored_token,
+                            given_value,
+                        } => Ok(format!(
+                            "{}\n",
+                            serde_json::to_string(&serde_json::json!({
+                                "status": "conflict",
+                                "stored": stored_value,
+                                "stored_token": stored_token,
+                                "given": given_value,
+                            }))?
+                        )),
+                    }
+                } else {
+                    Ok("ERR Usage: INSERT_IF_MATCH <key> <causal-token-json> <value>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: INSERT_IF_MATCH <key> <causal-token-json> <value>\n".to_string())
+            }
+        }
+        "DOT_GET" => {
+            if let Some(key) = cmd_parts.get(1) {
+                let snapshot = db.get_dotted(key);
+                Ok(format!(
+                    "{}\n",
+                    serde_json::to_string(&serde_json::json!({
+                        "values": snapshot.values,
+                        "context": snapshot.context,
+                    }))?
+                ))
+            } else {
+                Ok("ERR Usage: DOT_GET <key>\n".to_string())
+            }
+        }
+        "DOT_INSERT" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(3, ' ').collect();
+                if let (Some(key), Some(context_str), Some(value_str)) =
+                    (parts.get(0), parts.get(1), parts.get(2))
+                {
+                    let context: crate::versioning::VersionVector = serde_json::from_str(context_str)?;
+                    let value: Value = serde_json::from_str(value_str)?;
+                    let snapshot = db.insert_dotted(key.to_string(), context, value).await?;
+                    Ok(format!(
+                        "{}\n",
+                        serde_json::to_string(&serde_json::json!({
+                            "values": snapshot.values,
+                            "context": snapshot.context,
+                        }))?
+                    ))
+                } else {
+                    Ok("ERR Usage: DOT_INSERT <key> <causal-context-json> <value>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: DOT_INSERT <key> <causal-context-json> <value>\n".to_string())
+            }
+        }
+        "DOT_DELETE" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(key), Some(context_str)) = (parts.get(0), parts.get(1)) {
+                    let context: crate::versioning::VersionVector = serde_json::from_str(context_str)?;
+                    let snapshot = db.delete_dotted(key.to_string(), context).await?;
+                    Ok(format!(
+                        "{}\n",
+                        serde_json::to_string(&serde_json::json!({
+                            "values": snapshot.values,
+                            "context": snapshot.context,
+                        }))?
+                    ))
+                } else {
+                    Ok("ERR Usage: DOT_DELETE <key> <causal-context-json>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: DOT_DELETE <key> <causal-context-json>\n".to_string())
+            }
+        }
+        "IMPORT" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let entries: Vec<serde_json::Map<String, Value>> = serde_json::from_str(rest.trim())?;
+                let mut records = Vec::with_capacity(entries.len());
+                for mut entry in entries {
+                    let key = entry
+                        .remove("key")
+                        .and_then(|v| v.as_str().map(|s| s.to_string()))
+                        .ok_or("each IMPORT record needs a string \"key\"")?;
+                    let value = entry.remove("value").ok_or("each IMPORT record needs a \"value\"")?;
+                    records.push((key, value));
+                }
+                let task_id = db.import_async(records).await?;
+                Ok(format!("{}\n", serde_json::to_string(&serde_json::json!({ "task_id": task_id }))?))
+            } else {
+                Ok("ERR Usage: IMPORT <[{\"key\":..,\"value\":..}, ...]>\n".to_string())
+            }
+        }
+        "REINDEX" => {
+            let task_id = db.reindex_async().await?;
+            Ok(format!("{}\n", serde_json::to_string(&serde_json::json!({ "task_id": task_id }))?))
+        }
+        "TASK" => {
+            if let Some(id_str) = cmd_parts.get(1) {
+                let task_id: u64 = id_str.trim().parse()?;
+                match db.task_store.get(task_id) {
+                    Some(task) => Ok(format!("{}\n", serde_json::to_string(&task)?)),
+                    None => Ok("ERR Unknown task\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: TASK <id>\n".to_string())
+            }
+        }
+        "TASKS" => {
+            let limit = cmd_parts
+                .get(1)
+                .and_then(|s| s.trim().parse::<usize>().ok())
+                .unwrap_or(20);
+            Ok(format!("{}\n", serde_json::to_string(&db.task_store.recent(limit))?))
+        }
+        "RESOLVE" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(key), Some(value_str)) = (parts.get(0), parts.get(1)) {
+                    let value: Value = serde_json::from_str(value_str)?;
+                    let snapshot = db.resolve_dotted(key.to_string(), value).await?;
+                    Ok(format!(
+                        "{}\n",
+                        serde_json::to_string(&serde_json::json!({
+                            "values": snapshot.values,
+                            "context": snapshot.context,
+                        }))?
+                    ))
+                } else {
+                    Ok("ERR Usage: RESOLVE <key> <value>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: RESOLVE <key> <value>\n".to_string())
+            }
+        }
+        "SEARCH" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let search_parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(field), Some(query)) = (search_parts.get(0), search_parts.get(1)) {
+                    let results = db.search(field, query).await;
+                    let results: Vec<Value> = results
+                        .into_iter()
+                        .map(|(value, score)| serde_json::json!({ "value": value, "score": score }))
+                        .collect();
+                    Ok(format!("{}\n", serde_json::to_string(&results)?))
+                } else {
+                    Ok("ERR Usage: SEARCH <field> <query>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: SEARCH <field> <query>\n".to_string())
+            }
+        }
+        "MATCH" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let match_parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(field), Some(query)) = (match_parts.get(0), match_parts.get(1)) {
+                    let results = db.match_query(field, query).await;
+                    let results: Vec<Value> = results
+                        .into_iter()
+                        .map(|(value, score)| serde_json::json!({ "value": value, "score": score }))
+                        .collect();
+                    Ok(format!("{}\n", serde_json::to_string(&results)?))
+                } else {
+                    Ok("ERR Usage: MATCH <field> <query>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: MATCH <field> <query>\n".to_string())
+            }
+        }
+        "NEAR" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let near_parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let (Some(field), Some(point)) = (near_parts.get(0), near_parts.get(1)) {
+                    let results = db.near(field, point).await;
+                    let results: Vec<Value> = results
+                        .into_iter()
+                        .map(|(value, distance_meters)| serde_json::json!({ "value": value, "distance_meters": distance_meters }))
+                        .collect();
+                    Ok(format!("{}\n", serde_json::to_string(&results)?))
+                } else {
+                    Ok("ERR Usage: NEAR <field> <lon>,<lat>,<radius_meters>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: NEAR <field> <lon>,<lat>,<radius_meters>\n".to_string())
+            }
+        }
+        "RELOAD" => {
+            let new_config = crate::config::Config::load_from_file("config.json")?;
+            let report = db.reload_config(new_config).await?;
+            Ok(format!(
+                "{}\n",
+                serde_json::to_string(&serde_json::json!({
+                    "added_fields": report.added_fields,
+                    "removed_fields": report.removed_fields,
+                    "checkpoint_interval_secs": report.checkpoint_interval_secs,
+                }))?
+            ))
+        }
+        "CHECKPOINT" => {
+            if let Some(shard_id_str) = cmd_parts.get(1) {
+                let shard_id: u32 = shard_id_str.trim().parse()?;
+                db.checkpoint_shard(shard_id).await?;
+                Ok("OK\n".to_string())
+            } else {
+                Ok("ERR Usage: CHECKPOINT <shard_id>\n".to_string())
+            }
+        }
+        "INDEX_STATS" => {
+            if let Some(field) = cmd_parts.get(1) {
+                match db.index_stats(field.trim()).await {
+                    Some(stats) => {
+                        let counts: Vec<Value> = stats
+                            .counts
+                            .iter()
+                            .map(|(value, count)| serde_json::json!({ "value": value, "count": count }))
+                            .collect();
+                        Ok(format!(
+                            "{}\n",
+                            serde_json::to_string(&serde_json::json!({
+                                "field": field.trim(),
+                                "cardinality": stats.cardinality,
+                                "counts": counts,
+                            }))?
+                        ))
+                    }
+                    None => Ok("ERR Unknown or unsupported index\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: INDEX_STATS <field>\n".to_string())
+            }
+        }
+        "WATCH" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(3, ' ').collect();
+                if let (Some(key), Some(since_seq_str), Some(timeout_ms_str)) =
+                    (parts.get(0), parts.get(1), parts.get(2))
+                {
+                    let since_seq: u64 = since_seq_str.parse()?;
+                    let timeout_ms: u64 = timeout_ms_str.parse()?;
+                    let (value, seq) = db
+                        .watch_key(key, since_seq, std::time::Duration::from_millis(timeout_ms))
+                        .await;
+                    match value {
+                        Some(value) => Ok(format!(
+                            "{}\n",
+                            serde_json::to_string(&serde_json::json!({ "value": value, "seq": seq }))?
+                        )),
+                        None => Ok("NULL\n".to_string()),
+                    }
+                } else {
+                    Ok("ERR Usage: WATCH <key> <since_seq> <timeout_ms>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: WATCH <key> <since_seq> <timeout_ms>\n".to_string())
+            }
+        }
+        "WATCH_QUERY" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(5, ' ').collect();
+                if let (Some(field), Some(operator), Some(value), Some(since_seq_str), Some(timeout_ms_str)) =
+                    (parts.get(0), parts.get(1), parts.get(2), parts.get(3), parts.get(4))
+                {
+                    let since_seq: u64 = since_seq_str.parse()?;
+                    let timeout_ms: u64 = timeout_ms_str.parse()?;
+                    let (results, seq) = db
+                        .watch_query(field, operator, value, since_seq, std::time::Duration::from_millis(timeout_ms))
+                        .await;
+                    Ok(format!(
+                        "{}\n",
+                        serde_json::to_string(&serde_json::json!({ "results": results, "seq": seq }))?
+                    ))
+                } else {
+                    Ok("ERR Usage: WATCH_QUERY <field> <operator> <value> <since_seq> <timeout_ms>\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: WATCH_QUERY <field> <operator> <value> <since_seq> <timeout_ms>\n".to_string())
+            }
+        }
+        "CONVERT" => {
+            if let Some(dest_uri) = cmd_parts.get(1) {
+                let report = db.convert_backend(dest_uri.trim()).await?;
+                Ok(format!(
+                    "{}\n",
+                    serde_json::to_string(&serde_json::json!({
+                        "shards_migrated": report.shards_migrated,
+                        "records_migrated": report.records_migrated,
+                    }))?
+                ))
+            } else {
+                Ok("ERR Usage: CONVERT <dest_uri>\n".to_string())
+            }
+        }
+        "STATS" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                match parts.as_slice() {
+                    [field, value] => match db.index_value_count(field, value).await {
+                        Some(count) => Ok(format!(
+                            "{}\n",
+                            serde_json::to_string(&serde_json::json!({ "field": field, "value": value, "count": count }))?
+                        )),
+                        None => Ok("ERR Unknown or unsupported index\n".to_string()),
+                    },
+                    [field] => match db.index_stats(field).await {
+                        Some(stats) => Ok(format!(
+                            "{}\n",
+                            serde_json::to_string(&serde_json::json!({
+                                "field": field,
+                                "cardinality": stats.cardinality,
+                                "total_keys": stats.total_keys,
+                                "min": stats.min,
+                                "max": stats.max,
+                            }))?
+                        )),
+                        None => Ok("ERR Unknown or unsupported index\n".to_string()),
+                    },
+                    _ => Ok("ERR Usage: STATS <field> [value]\n".to_string()),
+                }
+            } else {
+                Ok("ERR Usage: STATS <field> [value]\n".to_string())
+            }
+        }
+        "POLL" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let parts: Vec<&str> = rest.trim().splitn(2, ' ').collect();
+                if let Some(key) = parts.get(0) {
+                    let timeout_ms: u64 = parts.get(1).map(|s| s.parse()).transpose()?.unwrap_or(30_000);
+                    // Seed `since_seq` with the current sequence so POLL always
+                    // waits for a change that happens after the call, unlike
+                    // WATCH where a caller-supplied `since_seq` may already be
+                    // behind -- POLL has no such cursor to resume from.
+                    let since_seq = db.change_feed.current_seq();
+                    let (value, _seq) = db
+                        .watch_key(key, since_seq, std::time::Duration::from_millis(timeout_ms))
+                        .await;
+                    match value {
+                        Some(value) => Ok(format!("{}\n", serde_json::to_string(&value)?)),
+                        None => Ok("NULL\n".to_string()),
+                    }
+                } else {
+                    Ok("ERR Usage: POLL <key> [timeout_ms]\n".to_string())
+                }
+            } else {
+                Ok("ERR Usage: POLL <key> [timeout_ms]\n".to_string())
+            }
+        }
+        "BATCH" => {
+            if let Some(rest) = cmd_parts.get(1) {
+                let ops: Vec<crate::hyperion_db::batch_method::BatchOp> = serde_json::from_str(rest.trim())?;
+                let results = db.execute_batch(ops).await;
+                Ok(format!("{}\n", serde_json::to_string(&results)?))
+            } else {
+                Ok("ERR Usage: BATCH <json array of ops>\n".to_string())
+            }
+        }
         "EXIT" => Ok("BYE\n".to_string()),
         _ => Ok("ERR Unknown command\n".to_string()),
     }
@@ -0,0 +1,13 @@
+use super::hyperion_db_struct::HyperionDB;
+use std::collections::HashSet;
+
+impl HyperionDB {
+    /// All keys across every shard, used to evaluate `NOT` in query
+    /// expressions (the complement of the negated condition's keys).
+    pub fn all_keys(&self) -> HashSet<String> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.value().iter().map(|entry| entry.key().clone()).collect::<Vec<_>>())
+            .collect()
+    }
+}
@@ -0,0 +1,76 @@
+use super::hyperion_db_struct::HyperionDB;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// One operation in a `BATCH` command, tagged by its `"op"` field (e.g.
+/// `{"op":"insert","key":"user1","value":{...}}`).
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchOp {
+    Insert { key: String, value: Value },
+    Delete { key: String },
+    Get { key: String },
+    Query { field: String, operator: String, value: String },
+}
+
+impl HyperionDB {
+    /// Runs a heterogeneous list of `BatchOp`s in one call and returns one
+    /// JSON result per op, in order. Inserts and deletes are not appended
+    /// to the WAL one by one as the single-op `insert`/`delete` commands
+    /// do; instead each touched shard is persisted once, after every op has
+    /// been applied, so a bulk load sent as a single `BATCH` pays for one
+    /// round-trip and one shard write per touched shard.
+    pub async fn execute_batch(&self, ops: Vec<BatchOp>) -> Vec<Value> {
+        let indexed_fields = self.indexed_fields.read().await.clone();
+        let mut touched_shards: HashSet<u32> = HashSet::new();
+        let mut results = Vec::with_capacity(ops.len());
+
+        for op in ops {
+            let result = match op {
+                BatchOp::Insert { key, value } => {
+                    let shard_id = self.shard_manager.get_shard(&key);
+                    match self.shards.get(&shard_id) {
+                        Some(shard) => {
+                            shard.insert(key.clone(), value.clone());
+                            self.indices.insert_into(shard_id, key.clone(), value.clone(), indexed_fields.clone()).await;
+                            self.change_feed.notify(shard_id, key);
+                            touched_shards.insert(shard_id);
+                            self.metrics.inc_inserts();
+                            serde_json::json!({ "status": "ok" })
+                        }
+                        None => serde_json::json!({ "status": "error", "message": "unknown shard" }),
+                    }
+                }
+                BatchOp::Delete { key } => {
+                    let shard_id = self.shard_manager.get_shard(&key);
+                    match self.shards.get(&shard_id).and_then(|shard| shard.remove(&key)) {
+                        Some((_, old_value)) => {
+                            self.indices.delete_from(shard_id, key.clone(), old_value, indexed_fields.clone()).await;
+                            self.change_feed.notify(shard_id, key);
+                            touched_shards.insert(shard_id);
+                            self.metrics.inc_deletes();
+                            serde_json::json!({ "status": "ok" })
+                        }
+                        None => serde_json::json!({ "status": "error", "message": "key not found" }),
+                    }
+                }
+                BatchOp::Get { key } => self.get(&key).await.unwrap_or(Value::Null),
+                BatchOp::Query { field, operator, value } => {
+                    Value::Array(self.query_condition(&field, &operator, &value).await)
+                }
+            };
+            results.push(result);
+        }
+
+        for shard_id in touched_shards {
+            if let Some(shard) = self.shards.get(&shard_id) {
+                if let Err(e) = self.shard_manager.persist_shard(shard_id, shard.clone()).await {
+                    eprintln!("BATCH: failed to persist shard {}: {}", shard_id, e);
+                }
+            }
+        }
+
+        results
+    }
+}
@@ -0,0 +1,29 @@
+use super::hyperion_db_struct::HyperionDB;
+use std::error::Error;
+
+impl HyperionDB {
+    /// Checkpoints `shard_id`: persists its current in-memory state through
+    /// the storage backend, records the WAL sequence number that snapshot
+    /// covers, then compacts the shard's WAL down to entries after it.
+    /// `checkpoint_seq` is sampled *before* the snapshot is taken, so every
+    /// entry up to it is guaranteed to already be reflected in the shard map
+    /// it's built from -- a write that races the snapshot gets a higher seq
+    /// and survives compaction instead of being silently dropped.
+    pub async fn checkpoint_shard(&self, shard_id: u32) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let shard = self
+            .shards
+            .get(&shard_id)
+            .ok_or_else(|| format!("Unknown shard: {}", shard_id))?
+            .clone();
+
+        let checkpoint_seq = self.wal_manager.current_seq(shard_id);
+
+        self.shard_manager.persist_shard(shard_id, shard).await?;
+        crate::storage::write_checkpoint_seq(&self.shard_manager.data_dir, shard_id, checkpoint_seq).await?;
+        self.wal_manager
+            .checkpoint(&self.shard_manager.data_dir, shard_id, checkpoint_seq)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use super::hyperion_db_struct::HyperionDB;
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// Marker field used on manifest records to indicate the real value is
+/// stored as content-defined chunks rather than inline.
+const CHUNK_MANIFEST_FIELD: &str = "__hyperiondb_chunks__";
+
+/// Values at or above this serialized size are chunked instead of stored
+/// inline, so that large JSON blobs get dedup benefits without paying the
+/// chunking overhead on ordinary small records.
+const CHUNKING_THRESHOLD_BYTES: usize = 4096;
+
+/// If `value` is a chunk manifest record (produced by `insert_chunked`),
+/// returns the chunk hashes it references. Used by the delete paths to
+/// release those chunks once the manifest itself is removed.
+pub(crate) fn chunk_hashes_of(value: &Value) -> Option<Vec<String>> {
+    value
+        .as_object()
+        .and_then(|obj| obj.get(CHUNK_MANIFEST_FIELD))
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect::<Vec<String>>()
+        })
+}
+
+impl HyperionDB {
+    /// Inserts `value`, transparently chunking and de-duplicating it via the
+    /// `ChunkStore` if it is large enough to be worth splitting.
+    pub async fn insert_chunked(&self, key: String, value: Value) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        let serialized = serde_json::to_vec(&value)?;
+
+        if serialized.len() < CHUNKING_THRESHOLD_BYTES {
+            return self.insert_or_update(key, value).await;
+        }
+
+        let hashes = self.chunk_store.put(&serialized).await?;
+        let manifest = json!({ CHUNK_MANIFEST_FIELD: hashes });
+        self.insert_or_update(key, manifest).await
+    }
+
+    /// Retrieves a value stored via `insert_chunked`, reassembling it from
+    /// its chunks if it was large enough to have been split. Falls back to
+    /// the ordinary `get` for values stored inline.
+    pub async fn get_chunked(&self, key: &str) -> Option<Value> {
+        let stored = self.get(key).await?;
+        let hashes = chunk_hashes_of(&stored);
+
+        match hashes {
+            Some(hashes) => {
+                let bytes = self.chunk_store.get(&hashes)?;
+                serde_json::from_slice(&bytes).ok()
+            }
+            None => Some(stored),
+        }
+    }
+}
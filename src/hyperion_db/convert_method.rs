@@ -0,0 +1,46 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::storage::backend_from_uri;
+use std::error::Error;
+
+/// Summary of a `convert_backend` migration.
+pub struct ConvertReport {
+    pub shards_migrated: u32,
+    pub records_migrated: usize,
+}
+
+impl HyperionDB {
+    /// Streams every shard's current in-memory state into the backend
+    /// selected by `dest_uri`, rebuilds `indices` from the migrated data,
+    /// then swaps the shard manager over to the new backend so later
+    /// writes land there. Backs the `CONVERT` command, which lets operators
+    /// move between storage engines (e.g. `file://` to `sqlite://`)
+    /// without dumping and reloading through the text protocol.
+    pub async fn convert_backend(&self, dest_uri: &str) -> Result<ConvertReport, Box<dyn Error + Send + Sync>> {
+        let dest_backend = backend_from_uri(dest_uri);
+        let indexed_fields = self.indexed_fields.read().await.clone();
+        let mut records_migrated = 0usize;
+
+        self.indices.clear().await;
+
+        for shard_id in 0..self.shard_manager.num_shards {
+            if let Some(shard) = self.shards.get(&shard_id) {
+                let shard = shard.clone();
+                dest_backend.save_shard(shard_id, shard.clone()).await?;
+                records_migrated += shard.len();
+
+                for entry in shard.iter() {
+                    self.indices
+                        .insert_into(shard_id, entry.key().clone(), entry.value().clone(), indexed_fields.clone())
+                        .await;
+                }
+            }
+        }
+
+        self.shard_manager.set_backend(dest_backend).await;
+
+        Ok(ConvertReport {
+            shards_migrated: self.shard_manager.num_shards,
+            records_migrated,
+        })
+    }
+}
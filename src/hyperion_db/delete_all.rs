@@ -1,6 +1,5 @@
+use super::chunked_value::chunk_hashes_of;
 use super::hyperion_db_struct::HyperionDB;
-use crate::index::update_indices_on_delete;
-use crate::storage::save_shard_to_disk;
 use std::error::Error as StdError;
 
 impl HyperionDB {
@@ -8,16 +7,18 @@ impl HyperionDB {
         for shard_entry in self.shards.iter() {
             let shard_id = *shard_entry.key();
             if let Some(shard) = self.shards.get(&shard_id) {
+                let indexed_fields = self.indexed_fields.read().await.clone();
                 for key in shard.iter().map(|entry| entry.key().clone()).collect::<Vec<String>>() {
                     if let Some((_, value)) = shard.remove(&key) {
-                        update_indices_on_delete(&self.indices, &key, &value, &self.indexed_fields).await;
+                        if let Some(hashes) = chunk_hashes_of(&value) {
+                            self.chunk_store.release(&hashes);
+                        }
+                        self.indices.delete_from(shard_id, key, value, indexed_fields.clone()).await;
                     }
                 }
-                save_shard_to_disk(&self.shard_manager.data_dir, shard_id, shard.clone()).await?;
+                self.shard_manager.persist_shard(shard_id, shard.clone()).await?;
             }
         }
         Ok(())
     }
-
- 
 }
@@ -1,24 +1,36 @@
 
+use super::chunked_value::chunk_hashes_of;
 use super::hyperion_db_struct::HyperionDB;
-use crate::index::update_indices_on_delete;
-use crate::storage::save_shard_to_disk;
+use crate::error::HyperionError;
 use std::collections::HashMap;
-use std::error::Error as StdError;
 
 impl HyperionDB {
-    pub async fn delete(&self, key: String) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    /// Like `insert`, records the delete as a WAL tombstone rather than
+    /// rewriting the whole shard file -- the shard only gets rewritten
+    /// (dropping the tombstone along with everything it superseded) at the
+    /// next `checkpoint_shard`.
+    pub async fn delete(&self, key: String) -> Result<(), HyperionError> {
         let shard_id = self.shard_manager.get_shard(&key);
         if let Some(shard) = self.shards.get(&shard_id) {
             if let Some((_, value)) = shard.remove(&key) {
-                update_indices_on_delete(&self.indices, &key, &value, &self.indexed_fields).await;
-                save_shard_to_disk(&self.shard_manager.data_dir, shard_id, shard.clone()).await?;
+                if let Some(hashes) = chunk_hashes_of(&value) {
+                    self.chunk_store.release(&hashes);
+                }
+                let indexed_fields = self.indexed_fields.read().await.clone();
+                self.indices.delete_from(shard_id, key.clone(), value, indexed_fields).await;
+                self.wal_manager
+                    .append_to_wal(&self.shard_manager.data_dir, shard_id, key.clone(), None)
+                    .await
+                    .map_err(HyperionError::from)?;
+                self.change_feed.notify(shard_id, key);
+                self.metrics.inc_deletes();
                 return Ok(());
             }
         }
-        Err("Key not found".into())
+        Err(HyperionError::KeyNotFound(key))
     }
 
-    pub async fn delete_many(&self, keys: Vec<String>) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    pub async fn delete_many(&self, keys: Vec<String>) -> Result<(), HyperionError> {
         let mut shard_batches: HashMap<u32, Vec<String>> = HashMap::new();
 
         for key in keys {
@@ -28,12 +40,19 @@ impl HyperionDB {
 
         for (shard_id, batch_keys) in shard_batches {
             if let Some(shard) = self.shards.get(&shard_id) {
+                let indexed_fields = self.indexed_fields.read().await.clone();
                 for key in &batch_keys {
                     if let Some((_, value)) = shard.remove(key) {
-                        update_indices_on_delete(&self.indices, key, &value, &self.indexed_fields).await;
+                        if let Some(hashes) = chunk_hashes_of(&value) {
+                            self.chunk_store.release(&hashes);
+                        }
+                        self.indices.delete_from(shard_id, key.clone(), value, indexed_fields.clone()).await;
                     }
                 }
-                save_shard_to_disk(&self.shard_manager.data_dir, shard_id, shard.clone()).await?;
+                self.shard_manager
+                    .persist_shard(shard_id, shard.clone())
+                    .await
+                    .map_err(HyperionError::from)?;
             }
         }
         Ok(())
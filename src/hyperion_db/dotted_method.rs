@@ -0,0 +1,108 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::versioning::VersionVector;
+use serde_json::Value;
+use std::error::Error;
+
+/// A key's surviving sibling values plus the causal-context token covering
+/// everything this node has seen for it, to hand back to the client.
+pub struct DottedSnapshot {
+    pub values: Vec<Value>,
+    pub context: VersionVector,
+}
+
+impl HyperionDB {
+    /// Reads every surviving sibling for `key` under the dotted-version-
+    /// vector-set path, along with the causal-context token to echo back on
+    /// the next `DOT_INSERT`/`DOT_DELETE`.
+    pub fn get_dotted(&self, key: &str) -> DottedSnapshot {
+        match self.dotted_records.get(key) {
+            Some(record) => DottedSnapshot {
+                values: record.live_values(),
+                context: record.context(),
+            },
+            None => DottedSnapshot {
+                values: Vec::new(),
+                context: VersionVector::new(),
+            },
+        }
+    }
+
+    /// Applies a write to `key` under causal context `context` (the token
+    /// the caller last read): siblings that context has already seen are
+    /// dropped, and `value` is added under a fresh dot. Indices are kept
+    /// consistent by reindexing every surviving sibling.
+    pub async fn insert_dotted(
+        &self,
+        key: String,
+        context: VersionVector,
+        value: Value,
+    ) -> Result<DottedSnapshot, Box<dyn Error + Send + Sync + 'static>> {
+        self.apply_dotted(key, context, Some(value)).await
+    }
+
+    /// Applies a tombstone delete to `key` under causal context `context`,
+    /// so a concurrent write racing the delete isn't silently resurrected.
+    pub async fn delete_dotted(
+        &self,
+        key: String,
+        context: VersionVector,
+    ) -> Result<DottedSnapshot, Box<dyn Error + Send + Sync + 'static>> {
+        self.apply_dotted(key, context, None).await
+    }
+
+    /// Collapses every surviving sibling for `key` into a single `value`.
+    /// Writes under the record's own current context, which by definition
+    /// dominates every dot it already holds, so `apply_dotted` drops all
+    /// existing siblings and replaces them with the one new write. Backs
+    /// the `RESOLVE` command, for clients that have looked at a sibling set
+    /// and decided on a merged/chosen value.
+    pub async fn resolve_dotted(
+        &self,
+        key: String,
+        value: Value,
+    ) -> Result<DottedSnapshot, Box<dyn Error + Send + Sync + 'static>> {
+        let context = self
+            .dotted_records
+            .get(&key)
+            .map(|record| record.context())
+            .unwrap_or_default();
+        self.apply_dotted(key, context, Some(value)).await
+    }
+
+    async fn apply_dotted(
+        &self,
+        key: String,
+        context: VersionVector,
+        value: Option<Value>,
+    ) -> Result<DottedSnapshot, Box<dyn Error + Send + Sync + 'static>> {
+        let previous_values = self
+            .dotted_records
+            .get(&key)
+            .map(|record| record.live_values())
+            .unwrap_or_default();
+
+        let mut record = self.dotted_records.entry(key.clone()).or_default();
+        record.apply(self.node_id, &context, value);
+        let new_values = record.live_values();
+        let new_context = record.context();
+        drop(record);
+
+        let shard_id = self.shard_manager.get_shard(&key);
+        let indexed_fields = self.indexed_fields.read().await.clone();
+        for old_value in &previous_values {
+            self.indices
+                .delete_from(shard_id, key.clone(), old_value.clone(), indexed_fields.clone())
+                .await;
+        }
+        for new_value in &new_values {
+            self.indices
+                .insert_into(shard_id, key.clone(), new_value.clone(), indexed_fields.clone())
+                .await;
+        }
+
+        Ok(DottedSnapshot {
+            values: new_values,
+            context: new_context,
+        })
+    }
+}
@@ -4,6 +4,7 @@ use serde_json::Value;
 
 impl HyperionDB {
     pub async fn get(&self, key: &str) -> Option<Value> {
+        self.metrics.inc_gets();
         let shard_id = self.shard_manager.get_shard(key);
         self.shards.get(&shard_id)?.get(key).map(|v| v.clone())
     }
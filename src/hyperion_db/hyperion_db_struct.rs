@@ -1,18 +1,64 @@
 
-use crate::index::Index;
+use crate::change_feed::ChangeNotifier;
+use crate::index::actor::IndexShards;
+use crate::metrics::Metrics;
 use crate::shard_manager::ShardManager;
-use crate::storage::WalManager;
+use crate::storage::{ChunkStore, WalManager};
+use crate::versioning::{DottedRecord, HybridClock, LwwStamp, VersionVector};
 use dashmap::DashMap;
 use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::RwLock;
 use crate::config::IndexedField;
 
 #[derive(Clone)]
 pub struct HyperionDB {
     pub shards: Arc<DashMap<u32, Arc<DashMap<String, Value>>>>,
-    pub indices: Arc<DashMap<String, Index>>,
+    /// One index actor per record shard (see `ShardManager::get_shard`):
+    /// each owns its slice of every field's index exclusively and processes
+    /// its mailbox sequentially, so a write to shard 3's indices never
+    /// blocks behind a write to shard 0's the way a single process-wide
+    /// `DashMap<String, Index>` would under sustained concurrent writers.
+    /// `insert`/`delete` message the owning shard directly; queries scatter
+    /// to every shard and merge the replies.
+    pub indices: Arc<IndexShards>,
     pub shard_manager: Arc<ShardManager>,
-    pub indexed_fields: Vec<IndexedField>,
+    /// Fields currently indexed, behind a lock so `RELOAD` can add/retire
+    /// entries on a running instance without restarting the server.
+    pub indexed_fields: Arc<RwLock<Vec<IndexedField>>>,
     pub wal_manager: Arc<WalManager>,
-
+    /// Last-writer-wins stamp recorded for each key, used to resolve
+    /// concurrent writes from different replicas deterministically.
+    pub lww_stamps: Arc<DashMap<String, LwwStamp>>,
+    /// Generates the timestamp half of every `LwwStamp` this node produces,
+    /// so `insert_lww` never trusts a client-supplied wall-clock value.
+    pub hlc: Arc<HybridClock>,
+    /// De-duplicated chunk storage backing `insert_chunked`/`get_chunked`.
+    pub chunk_store: Arc<ChunkStore>,
+    /// Operation counters exposed over the HTTP gateway's `/metrics` endpoint.
+    pub metrics: Arc<Metrics>,
+    /// Per-key causal version vectors backing `INSERT_IF_MATCH`'s optimistic
+    /// concurrency control. Appended to `versions.log` on every accepted
+    /// write (see `crate::versioning::append_version`) and replayed by
+    /// `load_versions` on startup, so a key's causal context survives a
+    /// restart instead of resetting to an empty token that any write would
+    /// trivially dominate.
+    pub versions: Arc<DashMap<String, VersionVector>>,
+    /// This node's id within its version vectors, taken from the
+    /// replication config when clustered, or `0` for a standalone node.
+    pub node_id: u32,
+    /// How often the background task checkpoints each shard, reloadable at
+    /// runtime via `reload_config`/`RELOAD`.
+    pub checkpoint_interval_secs: Arc<RwLock<Option<u64>>>,
+    /// Dotted-version-vector-set records backing `DOT_INSERT`/`DOT_GET`/
+    /// `DOT_DELETE`'s multi-writer-safe concurrent siblings, kept separate
+    /// from the plain `shards` map used by the LWW-over-DashMap write path.
+    pub dotted_records: Arc<DashMap<String, DottedRecord>>,
+    /// Broadcasts `insert`/`delete` changes so `WATCH`/`WATCH_QUERY` can
+    /// push updates to long-polling clients instead of making them spin on
+    /// `GET`.
+    pub change_feed: Arc<ChangeNotifier>,
+    /// Tracks background work spawned by `IMPORT`/`REINDEX` so clients can
+    /// poll `TASK`/`TASKS` instead of holding a connection open.
+    pub task_store: Arc<crate::tasks::TaskStore>,
 }
@@ -0,0 +1,17 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::index::IndexStats;
+
+impl HyperionDB {
+    /// Returns `field`'s per-value key counts, or `None` if `field` isn't
+    /// indexed (or is a `FullText` or `Geo` index, neither of which has a
+    /// notion of a per-value count). Backs the `INDEX_STATS` and `STATS` commands.
+    pub async fn index_stats(&self, field: &str) -> Option<IndexStats> {
+        self.indices.stats(field).await
+    }
+
+    /// Returns the number of keys indexed under exactly `value` for `field`,
+    /// or `None` if `field` isn't indexed. Backs `STATS <field> <value>`.
+    pub async fn index_value_count(&self, field: &str, value: &str) -> Option<usize> {
+        self.indices.count_for_value(field, value).await
+    }
+}
@@ -0,0 +1,59 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::versioning::VersionVector;
+use serde_json::Value;
+use std::error::Error;
+
+/// Outcome of a conditional `INSERT_IF_MATCH` write.
+pub enum CausalWriteResult {
+    /// The write was accepted; carries the key's new version token.
+    Applied(VersionVector),
+    /// The supplied token didn't dominate the stored version, i.e. someone
+    /// else wrote to this key after the caller last read it. Carries the
+    /// currently stored value/token plus the value the caller tried to
+    /// write, as sibling values for the caller to reconcile.
+    Conflict {
+        stored_value: Option<Value>,
+        stored_token: VersionVector,
+        given_value: Value,
+    },
+}
+
+impl HyperionDB {
+    /// Returns the causal version token currently recorded for `key`, or an
+    /// empty token if the key has never been written through a causal path.
+    pub fn version_for(&self, key: &str) -> VersionVector {
+        self.versions.get(key).map(|v| v.clone()).unwrap_or_default()
+    }
+
+    /// Applies `value` to `key` only if `token` dominates-or-equals the
+    /// stored version, i.e. the caller has seen every write reflected in the
+    /// stored version. This gives optimistic concurrency control: a client
+    /// that read stale data and tries to overwrite a concurrent update gets
+    /// back a `Conflict` instead of silently clobbering it.
+    pub async fn insert_if_match(
+        &self,
+        key: String,
+        token: VersionVector,
+        value: Value,
+    ) -> Result<CausalWriteResult, Box<dyn Error + Send + Sync + 'static>> {
+        let stored = self.version_for(&key);
+
+        if !token.dominates_or_equal(&stored) {
+            return Ok(CausalWriteResult::Conflict {
+                stored_value: self.get(&key).await,
+                stored_token: stored,
+                given_value: value,
+            });
+        }
+
+        let mut new_token = stored;
+        new_token.merge(&token);
+        new_token.increment(self.node_id);
+
+        self.versions.insert(key.clone(), new_token.clone());
+        crate::versioning::append_version(&self.shard_manager.data_dir, &key, &new_token).await?;
+        self.insert_or_update(key, value).await?;
+
+        Ok(CausalWriteResult::Applied(new_token))
+    }
+}
@@ -0,0 +1,35 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::versioning::LwwStamp;
+use serde_json::Value;
+use std::error::Error;
+
+impl HyperionDB {
+    /// Inserts `value` for `key` tagged with a last-writer-wins stamp whose
+    /// timestamp comes from this node's `HybridClock` rather than the
+    /// caller, so a client can't pin a key forever by supplying an
+    /// arbitrary future timestamp.
+    ///
+    /// If a later (or tie-broken higher `node_id`) write has already been
+    /// recorded for this key, the incoming write is dropped so that replicas
+    /// converge on the same value regardless of delivery order. Returns
+    /// whether the write was applied.
+    pub async fn insert_lww(
+        &self,
+        key: String,
+        value: Value,
+    ) -> Result<bool, Box<dyn Error + Send + Sync + 'static>> {
+        let stamp = LwwStamp::new(self.hlc.tick(), self.node_id);
+        let applies = match self.lww_stamps.get(&key) {
+            Some(existing) => stamp.wins_over(&existing),
+            None => true,
+        };
+
+        if !applies {
+            return Ok(false);
+        }
+
+        self.lww_stamps.insert(key.clone(), stamp);
+        self.insert_or_update(key, value).await?;
+        Ok(true)
+    }
+}
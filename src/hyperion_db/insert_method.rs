@@ -1,18 +1,34 @@
 
 use super::hyperion_db_struct::HyperionDB;
-use crate::index::update_indices_on_insert;
-use crate::storage::save_shard_to_disk;
+use crate::error::HyperionError;
+use crate::storage::WalManager;
 use serde_json::Value;
-use std::error::Error;
+use std::sync::Arc;
 
 impl HyperionDB {
-    pub async fn insert(&self, key: String, value: Value) -> Result<(), Box<dyn Error>> {
+    /// Durability here is append-only: the write lands in the shard's WAL,
+    /// which is O(1) and crash-safe, rather than rewriting the whole shard
+    /// file on every call. The full shard only gets rewritten when
+    /// `checkpoint_shard` next runs (on `checkpoint_interval_secs` or via
+    /// `CHECKPOINT`), at which point the WAL it supersedes
+    /// is truncated.
+    pub async fn insert(&self, key: String, value: Value) -> Result<(), HyperionError> {
         let shard_id = self.shard_manager.get_shard(&key);
         if let Some(shard) = self.shards.get(&shard_id) {
             shard.insert(key.clone(), value.clone());
-            update_indices_on_insert(&self.indices, &key, &value, &self.indexed_fields).await;
-            save_shard_to_disk(&self.shard_manager.data_dir, shard_id, shard.clone()).await?;
+            let indexed_fields = self.indexed_fields.read().await.clone();
+            self.indices.insert_into(shard_id, key.clone(), value.clone(), indexed_fields).await;
+
+            let wal_manager: Arc<WalManager> = Arc::clone(&self.wal_manager);
+            let data_dir = self.shard_manager.data_dir.clone();
+            wal_manager
+                .append_to_wal(&data_dir, shard_id, key.clone(), Some(value))
+                .await
+                .map_err(HyperionError::from)?;
+
+            self.change_feed.notify(shard_id, key);
         }
+        self.metrics.inc_inserts();
         Ok(())
     }
 }
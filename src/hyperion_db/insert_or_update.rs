@@ -1,7 +1,4 @@
-use crate::{
-    index::update_indices_on_insert,
-    storage::WalManager,
-};
+use crate::storage::WalManager;
 use serde_json::Value;
 use std::error::Error;
 use futures::stream::{self, StreamExt};
@@ -24,12 +21,14 @@ impl HyperionDB {
                 let wal_manager: Arc<WalManager> = Arc::clone(&self.wal_manager);
                 let data_dir = data_dir.clone();
                 async move {
-                    wal_manager.append_to_wal(&data_dir, shard_id, key_clone, value_clone).await.unwrap();
+                    wal_manager.append_to_wal(&data_dir, shard_id, key_clone, Some(value_clone)).await.unwrap();
                 }
             });
 
             // Actualiza los índices si es necesario
-            update_indices_on_insert(&self.indices, &key, &value, &self.indexed_fields).await;
+            let indexed_fields = self.indexed_fields.read().await.clone();
+            self.indices.insert_into(shard_id, key.clone(), value.clone(), indexed_fields).await;
+            self.metrics.inc_inserts();
         }
         Ok(())
     }
@@ -41,7 +40,7 @@ impl HyperionDB {
         let batch_size = 10000;
         let data_dir = self.shard_manager.data_dir.clone();
         let indices = self.indices.clone();
-        let indexed_fields = self.indexed_fields.clone();
+        let indexed_fields = self.indexed_fields.read().await.clone();
         let shard_manager = Arc::clone(&self.shard_manager);
         let shards = Arc::clone(&self.shards);
         let wal_manager: Arc<WalManager> = Arc::clone(&self.wal_manager);
@@ -62,12 +61,12 @@ impl HyperionDB {
                             shard.insert(key.clone(), value.clone());
 
                             // Utiliza WalManager para la escritura en el WAL
-                            if let Err(e) = wal_manager.append_to_wal(&data_dir, shard_id, key.clone(), value.clone()).await {
+                            if let Err(e) = wal_manager.append_to_wal(&data_dir, shard_id, key.clone(), Some(value.clone())).await {
                                 eprintln!("Error al escribir en el WAL: {}", e);
                             }
 
                             // Actualiza los índices
-                            update_indices_on_insert(&indices, &key, &value, &indexed_fields).await;
+                            indices.insert_into(shard_id, key.clone(), value.clone(), indexed_fields.clone()).await;
                         }
                     }
                 }
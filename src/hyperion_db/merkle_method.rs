@@ -0,0 +1,30 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::replication::MerkleTree;
+use std::collections::HashMap;
+
+impl HyperionDB {
+    /// Builds a `MerkleTree` over the current contents of `shard_id`.
+    pub fn merkle_tree_for_shard(&self, shard_id: u32) -> Option<MerkleTree> {
+        let shard = self.shards.get(&shard_id)?;
+        let entries: Vec<(String, String)> = shard
+            .iter()
+            .map(|kv| (kv.key().clone(), kv.value().to_string()))
+            .collect();
+        Some(MerkleTree::build(&entries))
+    }
+
+    /// Root digest for `shard_id`, used to cheaply check whether two
+    /// replicas of the shard have diverged.
+    pub fn merkle_root(&self, shard_id: u32) -> Option<String> {
+        self.merkle_tree_for_shard(shard_id).map(|tree| tree.root().to_string())
+    }
+
+    /// Given the leaf hashes reported by a remote replica for `shard_id`,
+    /// returns the keys that diverge and must be re-synced.
+    pub fn merkle_diff(&self, shard_id: u32, remote_leaves: &HashMap<String, String>) -> Vec<String> {
+        match self.merkle_tree_for_shard(shard_id) {
+            Some(tree) => tree.diff(remote_leaves),
+            None => remote_leaves.keys().cloned().collect(),
+        }
+    }
+}
@@ -7,4 +7,21 @@ pub mod get_all_records_method;
 pub mod query_multiple;
 pub mod insert_or_update;
 pub mod delete_all;
+pub mod merkle_method;
+pub mod insert_lww;
+pub mod chunked_value;
+pub mod upgrade_method;
+pub mod query_condition;
+pub mod all_keys_method;
+pub mod shard_sizes_method;
+pub mod checkpoint_method;
+pub mod insert_if_match;
+pub mod reload_method;
+pub mod search_method;
+pub mod dotted_method;
+pub mod convert_method;
+pub mod watch_method;
+pub mod index_stats_method;
+pub mod batch_method;
+pub mod task_method;
 pub use hyperion_db_struct::HyperionDB;
@@ -1,39 +1,68 @@
 use super::hyperion_db_struct::HyperionDB;
 use crate::config::Config;
-use crate::storage::load_shard_from_disk;
-use crate::{index::update_indices_on_insert, shard_manager::ShardManager};
+use crate::{index::actor::IndexShards, shard_manager::ShardManager};
 use dashmap::DashMap;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
+use tokio::sync::RwLock;
 use crate::storage::load_from_wal;
+use super::chunked_value::chunk_hashes_of;
 
 impl HyperionDB {
     
     
     pub async fn new(config: Config) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let shards = Arc::new(DashMap::new());
-        let indices = Arc::new(DashMap::new());
         let shard_manager = Arc::new(ShardManager::new(config.num_shards, config.data_dir.clone()).await?);
+        let indices = Arc::new(IndexShards::new(shard_manager.num_shards));
+        let node_id = config.replication.as_ref().map(|r| r.node_id).unwrap_or(0);
+        let checkpoint_interval_secs = config.checkpoint_interval_secs;
+        let task_store = Arc::new(crate::tasks::TaskStore::load(&config.data_dir).await?);
+        let chunk_store = Arc::new(crate::storage::ChunkStore::load(&config.data_dir).await?);
+        let wal_manager = Arc::new(crate::storage::WalManager::new((0..shard_manager.num_shards).collect()));
+        let versions = Arc::new(crate::versioning::load_versions(&config.data_dir).await?);
 
         for shard_id in 0..shard_manager.num_shards {
-            // Ahora `load_shard_from_disk` devuelve un HashMap<String, Value>
-            let shard_data: HashMap<String, Value> = load_shard_from_disk(&config.data_dir, shard_id).await?;
+            // Carga vía el backend de almacenamiento configurado (filesystem, memoria, lmdb o sqlite)
+            let shard_data: HashMap<String, Value> = shard_manager.load_shard(shard_id).await?;
 
             // Convertimos el HashMap a DashMap y lo insertamos en los shards
             let shard = Arc::new(DashMap::from_iter(shard_data.into_iter()));
             shards.insert(shard_id, shard.clone());
 
-            load_from_wal(&shard, shard_id).await?; // Llamada corregida a `load_from_wal`
+            // The snapshot just loaded already covers every WAL entry up to
+            // its recorded checkpoint_seq, so replay only needs entries
+            // after that; seed the live counter from whichever of the two
+            // (recovered checkpoint, or the WAL's own highest seq) is larger.
+            let checkpoint_seq = crate::storage::read_checkpoint_seq(&config.data_dir, shard_id).await;
+            let max_wal_seq = load_from_wal(&config.data_dir, &shard, shard_id, checkpoint_seq).await?;
+            wal_manager.seed_seq(shard_id, checkpoint_seq.max(max_wal_seq));
 
             for entry in shard.iter() {
-                update_indices_on_insert(
-                    &indices,
-                    entry.key(),
-                    entry.value(),
-                    &config.indexed_fields,
-                ).await;
+                // Indices aren't persisted to disk -- only the records are --
+                // so rebuilding them here from `shard` on every startup also
+                // means any change to how a field gets encoded into its
+                // index (e.g. `Index::Numeric`'s ordered-`u64` encoding)
+                // takes effect for existing data with no separate migration
+                // step.
+                indices
+                    .insert_into(
+                        shard_id,
+                        entry.key().clone(),
+                        entry.value().clone(),
+                        config.indexed_fields.clone(),
+                    )
+                    .await;
+
+                // Same rationale as the indices above: chunk refcounts
+                // aren't persisted directly, so they're rebuilt here from
+                // how many manifest records loaded from disk actually
+                // reference each chunk.
+                if let Some(hashes) = chunk_hashes_of(entry.value()) {
+                    chunk_store.adopt(&hashes);
+                }
             }
         }
 
@@ -41,7 +70,18 @@ impl HyperionDB {
             shards,
             indices,
             shard_manager,
-            indexed_fields: config.indexed_fields,
+            indexed_fields: Arc::new(RwLock::new(config.indexed_fields)),
+            lww_stamps: Arc::new(DashMap::new()),
+            hlc: Arc::new(crate::versioning::HybridClock::new()),
+            chunk_store,
+            metrics: Arc::new(crate::metrics::Metrics::new()),
+            versions,
+            node_id,
+            checkpoint_interval_secs: Arc::new(RwLock::new(checkpoint_interval_secs)),
+            dotted_records: Arc::new(DashMap::new()),
+            change_feed: Arc::new(crate::change_feed::ChangeNotifier::new((0..shard_manager.num_shards).collect())),
+            task_store,
+            wal_manager,
         })
     }
 }
\ No newline at end of file
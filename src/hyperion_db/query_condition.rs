@@ -0,0 +1,135 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::index::geo;
+use serde_json::Value;
+use std::collections::HashSet;
+
+impl HyperionDB {
+    /// Runs a single `field operator value` condition against that field's
+    /// index (supports `=`, `!=`, `>`, `>=`, `<`, `<=`, `RANGE`, `CONTAINS`,
+    /// `PREFIX`, `WITHIN_BBOX`, and `NEAR`, depending on the index type).
+    pub async fn query_condition(&self, field: &str, operator: &str, value: &str) -> Vec<Value> {
+        self.metrics.inc_queries();
+        let keys = self.indices.query(field, operator, value).await;
+        let keys = self.geo_filter_keys(field, operator, value, keys).await;
+
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(value) = self.get(&key).await {
+                results.push(value);
+            }
+        }
+        results
+    }
+
+    /// Drops cell-quantization false positives from `WITHIN_BBOX`/`NEAR`
+    /// candidate keys by re-checking each one's exact lat/lon against
+    /// `operator`/`value`; every other operator passes `keys` through
+    /// unchanged. `Index::Geo` only ever sees Morton cell keys, so
+    /// `indices.query` can only hand back the coarse cell-range candidates
+    /// for those two operators -- this is the first point with record
+    /// access, so it's where the exact containment/distance check happens.
+    /// Shared by `query_condition` and `evaluate_expr`, so a `QUERY`-driven
+    /// geo lookup gets the same exact filtering as the dedicated command.
+    pub(super) async fn geo_filter_keys(
+        &self,
+        field: &str,
+        operator: &str,
+        value: &str,
+        keys: HashSet<String>,
+    ) -> HashSet<String> {
+        match operator {
+            "WITHIN_BBOX" => {
+                let Some((min_lon, min_lat, max_lon, max_lat)) = parse_bbox(value) else {
+                    return keys;
+                };
+                let mut filtered = HashSet::with_capacity(keys.len());
+                for key in keys {
+                    if let Some(record) = self.get(&key).await {
+                        if point_for_field(&record, field).is_some_and(|(lon, lat)| {
+                            lon >= min_lon && lon <= max_lon && lat >= min_lat && lat <= max_lat
+                        }) {
+                            filtered.insert(key);
+                        }
+                    }
+                }
+                filtered
+            }
+            "NEAR" => {
+                let Some((center_lon, center_lat, radius_m)) = parse_near(value) else {
+                    return keys;
+                };
+                let mut filtered = HashSet::with_capacity(keys.len());
+                for key in keys {
+                    if let Some(record) = self.get(&key).await {
+                        if point_for_field(&record, field).is_some_and(|(lon, lat)| {
+                            geo::haversine_meters(center_lon, center_lat, lon, lat) <= radius_m
+                        }) {
+                            filtered.insert(key);
+                        }
+                    }
+                }
+                filtered
+            }
+            _ => keys,
+        }
+    }
+
+    /// Like `query_condition`'s `NEAR` handling, but also returns each
+    /// match's distance in meters and orders results nearest-first, the
+    /// way `search`/`match_query` return a relevance score alongside each
+    /// `FullText` hit. Backs the `NEAR` command.
+    pub async fn near(&self, field: &str, value: &str) -> Vec<(Value, f64)> {
+        self.metrics.inc_queries();
+        let keys = self.indices.query(field, "NEAR", value).await;
+        let Some((center_lon, center_lat, radius_m)) = parse_near(value) else {
+            return Vec::new();
+        };
+
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(record) = self.get(&key).await {
+                if let Some((lon, lat)) = point_for_field(&record, field) {
+                    let distance = geo::haversine_meters(center_lon, center_lat, lon, lat);
+                    if distance <= radius_m {
+                        results.push((record, distance));
+                    }
+                }
+            }
+        }
+        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        results
+    }
+
+    /// Runs several conditions in a single call, returning one result set
+    /// per condition in the same order. Lets clients batch what would
+    /// otherwise be several separate `QUERY` round-trips.
+    pub async fn query_batch(&self, conditions: &[(String, String, String)]) -> Vec<Vec<Value>> {
+        let mut results = Vec::with_capacity(conditions.len());
+        for (field, operator, value) in conditions {
+            results.push(self.query_condition(field, operator, value).await);
+        }
+        results
+    }
+}
+
+fn point_for_field(record: &Value, field: &str) -> Option<(f64, f64)> {
+    let obj = record.as_object()?;
+    let field_value = crate::index::get_nested_field(obj, field)?;
+    geo::point_from_value(field_value)
+}
+
+fn parse_bbox(value: &str) -> Option<(f64, f64, f64, f64)> {
+    let parts: Vec<f64> = value.split(',').map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+    match parts[..] {
+        [min_lon, min_lat, max_lon, max_lat] => Some((min_lon, min_lat, max_lon, max_lat)),
+        _ => None,
+    }
+}
+
+fn parse_near(value: &str) -> Option<(f64, f64, f64)> {
+    let parts: Vec<f64> = value.split(',').map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+    match parts[..] {
+        [lon, lat, radius_m] => Some((lon, lat, radius_m)),
+        _ => None,
+    }
+}
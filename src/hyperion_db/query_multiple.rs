@@ -2,15 +2,85 @@ use std::collections::HashSet;
 use std::future::Future;
 use std::pin::Pin;
 
-use serde_json::Value;
+use serde_json::{Map, Value};
 
+use crate::error::HyperionError;
 use crate::handler::Expr;
+use crate::index::validate_operator;
 
 use super::HyperionDB;
 
+/// Looks up a dot-separated field path in a JSON object, mirroring
+/// `index::get_nested_field`'s traversal so the full-scan fallback agrees
+/// with how indexed lookups address nested values.
+fn get_field<'a>(map: &'a Map<String, Value>, field: &str) -> Option<&'a Value> {
+    let parts: Vec<&str> = field.split('.').collect();
+    let mut current = map;
+    for (i, part) in parts.iter().enumerate() {
+        let value = current.get(*part)?;
+        if i == parts.len() - 1 {
+            return Some(value);
+        }
+        match value {
+            Value::Object(obj) => current = obj,
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Evaluates a single `field operator value` condition against one record
+/// by comparing raw JSON values, used as the full-scan fallback when
+/// `field` has no index to answer the condition from.
+fn record_matches(record: &Value, field: &str, operator: &str, raw_value: &str) -> bool {
+    let Value::Object(map) = record else {
+        return false;
+    };
+    let Some(field_value) = get_field(map, field) else {
+        return false;
+    };
+
+    match field_value {
+        Value::Number(num) => {
+            let Some(n) = num.as_f64() else {
+                return false;
+            };
+            match operator {
+                "=" => raw_value.parse::<f64>().is_ok_and(|v| n == v),
+                "!=" => raw_value.parse::<f64>().is_ok_and(|v| n != v),
+                ">" => raw_value.parse::<f64>().is_ok_and(|v| n > v),
+                ">=" => raw_value.parse::<f64>().is_ok_and(|v| n >= v),
+                "<" => raw_value.parse::<f64>().is_ok_and(|v| n < v),
+                "<=" => raw_value.parse::<f64>().is_ok_and(|v| n <= v),
+                "RANGE" => raw_value
+                    .split_once(':')
+                    .and_then(|(min, max)| Some((min.parse::<f64>().ok()?, max.parse::<f64>().ok()?)))
+                    .is_some_and(|(min, max)| n >= min && n <= max),
+                "IN" => raw_value
+                    .split(',')
+                    .any(|candidate| candidate.trim().parse::<f64>().is_ok_and(|v| v == n)),
+                _ => false,
+            }
+        }
+        Value::String(s) => match operator {
+            "=" => s == raw_value,
+            "!=" => s != raw_value,
+            "CONTAINS" => s.contains(raw_value),
+            "PREFIX" => s.starts_with(raw_value),
+            "RANGE" => raw_value
+                .split_once(':')
+                .is_some_and(|(min, max)| s.as_str() >= min && s.as_str() <= max),
+            "IN" => raw_value.split(',').any(|candidate| candidate.trim() == s),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 impl HyperionDB {
-    pub async fn query_expression(&self, expr: &Expr) -> Vec<Value> {
-        let keys = self.evaluate_expr(expr).await;
+    pub async fn query_expression(&self, expr: &Expr) -> Result<Vec<Value>, HyperionError> {
+        self.metrics.inc_queries();
+        let keys = self.evaluate_expr(expr).await?;
         let mut result_values = Vec::new();
 
         for key in keys {
@@ -19,31 +89,63 @@ impl HyperionDB {
             }
         }
 
-        result_values
+        Ok(result_values)
     }
 
-    fn evaluate_expr<'a>(&'a self, expr: &'a Expr) -> Pin<Box<dyn Future<Output = HashSet<String>> + Send + 'a>> {
+    /// Full scan over every shard, kept as the fallback for predicates on
+    /// fields nobody indexed. `evaluate_expr` calls it for each such leaf
+    /// condition in the order the expression is written, and it always
+    /// checks every record; it is not narrowed to the keys that the indexed
+    /// conditions of the same expression matched.
+    fn scan_keys_for_condition(&self, field: &str, operator: &str, value: &str) -> HashSet<String> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                shard
+                    .value()
+                    .iter()
+                    .filter(|entry| record_matches(entry.value(), field, operator, value))
+                    .map(|entry| entry.key().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn evaluate_expr<'a>(
+        &'a self,
+        expr: &'a Expr,
+    ) -> Pin<Box<dyn Future<Output = Result<HashSet<String>, HyperionError>> + Send + 'a>> {
         Box::pin(async move {
             match expr {
                 Expr::Condition(cond) => {
-                    if let Some(index) = self.indices.get(&cond.field) {
-                        index.query_keys(&cond.operator, &cond.value)
+                    if self.indices.field_exists(&cond.field).await {
+                        if let Some(indexed_field) =
+                            self.indexed_fields.read().await.iter().find(|f| f.field == cond.field)
+                        {
+                            validate_operator(&cond.field, &indexed_field.index_type, &cond.operator)?;
+                        }
+                        let keys = self.indices.query(&cond.field, &cond.operator, &cond.value).await;
+                        Ok(self.geo_filter_keys(&cond.field, &cond.operator, &cond.value, keys).await)
                     } else {
-                        HashSet::new()
+                        Ok(self.scan_keys_for_condition(&cond.field, &cond.operator, &cond.value))
                     }
                 }
                 Expr::And(lhs, rhs) => {
-                    let left_keys = self.evaluate_expr(lhs).await;
+                    let left_keys = self.evaluate_expr(lhs).await?;
                     if left_keys.is_empty() {
-                        return HashSet::new();
+                        return Ok(HashSet::new());
                     }
-                    let right_keys = self.evaluate_expr(rhs).await;
-                    left_keys.intersection(&right_keys).cloned().collect()
+                    let right_keys = self.evaluate_expr(rhs).await?;
+                    Ok(left_keys.intersection(&right_keys).cloned().collect())
                 }
                 Expr::Or(lhs, rhs) => {
-                    let left_keys = self.evaluate_expr(lhs).await;
-                    let right_keys = self.evaluate_expr(rhs).await;
-                    left_keys.union(&right_keys).cloned().collect()
+                    let left_keys = self.evaluate_expr(lhs).await?;
+                    let right_keys = self.evaluate_expr(rhs).await?;
+                    Ok(left_keys.union(&right_keys).cloned().collect())
+                }
+                Expr::Not(inner) => {
+                    let inner_keys = self.evaluate_expr(inner).await?;
+                    Ok(self.all_keys().difference(&inner_keys).cloned().collect())
                 }
                 Expr::Group(inner) => self.evaluate_expr(inner).await,
             }
@@ -0,0 +1,64 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::config::Config;
+use std::error::Error;
+
+/// Summary of what a `reload_config` call changed.
+pub struct ReloadReport {
+    pub added_fields: Vec<String>,
+    pub removed_fields: Vec<String>,
+    pub checkpoint_interval_secs: Option<u64>,
+}
+
+impl HyperionDB {
+    /// Diffs `new_config.indexed_fields` against the fields currently
+    /// indexed and applies the difference live, without dropping
+    /// connections or restarting the server: newly added fields are
+    /// backfilled by scanning every shard's existing values, removed
+    /// fields have their index structures dropped. The checkpoint interval
+    /// is swapped in immediately so the background checkpoint loop picks
+    /// it up on its next tick.
+    pub async fn reload_config(&self, new_config: Config) -> Result<ReloadReport, Box<dyn Error + Send + Sync>> {
+        let mut current = self.indexed_fields.write().await;
+
+        let added: Vec<_> = new_config
+            .indexed_fields
+            .iter()
+            .filter(|field| !current.iter().any(|existing| existing.field == field.field))
+            .cloned()
+            .collect();
+
+        let removed: Vec<_> = current
+            .iter()
+            .filter(|existing| !new_config.indexed_fields.iter().any(|field| field.field == existing.field))
+            .cloned()
+            .collect();
+
+        for field in &added {
+            let single_field = vec![field.clone()];
+            for shard in self.shards.iter() {
+                let shard_id = *shard.key();
+                for entry in shard.iter() {
+                    self.indices
+                        .insert_into(shard_id, entry.key().clone(), entry.value().clone(), single_field.clone())
+                        .await;
+                }
+            }
+        }
+
+        for field in &removed {
+            self.indices.remove_field(&field.field).await;
+        }
+
+        *current = new_config.indexed_fields;
+        drop(current);
+
+        let mut interval = self.checkpoint_interval_secs.write().await;
+        *interval = new_config.checkpoint_interval_secs;
+
+        Ok(ReloadReport {
+            added_fields: added.into_iter().map(|f| f.field).collect(),
+            removed_fields: removed.into_iter().map(|f| f.field).collect(),
+            checkpoint_interval_secs: *interval,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use super::hyperion_db_struct::HyperionDB;
+use serde_json::Value;
+
+impl HyperionDB {
+    /// Runs a typo-tolerant full-text search of `query` against `field`'s
+    /// `FullText` index, returning `(value, score)` pairs ordered by
+    /// descending match score. Returns an empty result if `field` isn't
+    /// indexed as `FullText`.
+    pub async fn search(&self, field: &str, query: &str) -> Vec<(Value, usize)> {
+        self.metrics.inc_queries();
+
+        let ranked = self.indices.search(field, query).await;
+
+        let mut results = Vec::with_capacity(ranked.len());
+        for (key, score) in ranked {
+            if let Some(value) = self.get(&key).await {
+                results.push((value, score));
+            }
+        }
+        results
+    }
+
+    /// Like `search`, but ranks by BM25 (`tf * idf`) instead of raw term-match
+    /// count, so relevance reflects how often and how distinctively each
+    /// query term occurs rather than just how many query terms matched.
+    /// Backs the `MATCH` command.
+    pub async fn match_query(&self, field: &str, query: &str) -> Vec<(Value, f64)> {
+        self.metrics.inc_queries();
+
+        let ranked = self.indices.match_query(field, query).await;
+
+        let mut results = Vec::with_capacity(ranked.len());
+        for (key, score) in ranked {
+            if let Some(value) = self.get(&key).await {
+                results.push((value, score));
+            }
+        }
+        results
+    }
+}
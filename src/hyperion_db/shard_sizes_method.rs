@@ -0,0 +1,15 @@
+use super::hyperion_db_struct::HyperionDB;
+
+impl HyperionDB {
+    /// Number of records currently held by each shard, used for per-shard
+    /// instrumentation on the `/metrics` endpoint.
+    pub fn shard_sizes(&self) -> Vec<(u32, usize)> {
+        let mut sizes: Vec<(u32, usize)> = self
+            .shards
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().len()))
+            .collect();
+        sizes.sort_by_key(|(shard_id, _)| *shard_id);
+        sizes
+    }
+}
@@ -0,0 +1,72 @@
+use super::hyperion_db_struct::HyperionDB;
+use serde_json::Value;
+use std::error::Error;
+
+impl HyperionDB {
+    /// Enqueues `records` for insertion on a background `tokio` task and
+    /// returns its task id immediately, so a bulk load doesn't hold the
+    /// caller's connection open for the whole batch. Backs `IMPORT`; poll
+    /// progress with `TASK <id>`.
+    pub async fn import_async(&self, records: Vec<(String, Value)>) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let task_id = self.task_store.enqueue("import").await?;
+        let db = self.clone();
+
+        tokio::spawn(async move {
+            let _ = db.task_store.mark_processing(task_id).await;
+
+            let mut imported = 0usize;
+            for (key, value) in records {
+                match db.insert(key, value).await {
+                    Ok(()) => imported += 1,
+                    Err(e) => {
+                        let _ = db
+                            .task_store
+                            .mark_failed(task_id, format!("failed after {} records: {}", imported, e))
+                            .await;
+                        return;
+                    }
+                }
+            }
+
+            let _ = db
+                .task_store
+                .mark_succeeded(task_id, serde_json::json!({ "imported": imported }))
+                .await;
+        });
+
+        Ok(task_id)
+    }
+
+    /// Rebuilds every index from the records currently held in `shards` on
+    /// a background `tokio` task, returning its task id immediately. Backs
+    /// `REINDEX`, for recovering from a corrupted index or picking up a
+    /// `RELOAD`-ed field without restarting the server.
+    pub async fn reindex_async(&self) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let task_id = self.task_store.enqueue("reindex").await?;
+        let db = self.clone();
+
+        tokio::spawn(async move {
+            let _ = db.task_store.mark_processing(task_id).await;
+
+            db.indices.clear().await;
+            let indexed_fields = db.indexed_fields.read().await.clone();
+            let mut reindexed = 0usize;
+            for shard in db.shards.iter() {
+                let shard_id = *shard.key();
+                for entry in shard.value().iter() {
+                    db.indices
+                        .insert_into(shard_id, entry.key().clone(), entry.value().clone(), indexed_fields.clone())
+                        .await;
+                    reindexed += 1;
+                }
+            }
+
+            let _ = db
+                .task_store
+                .mark_succeeded(task_id, serde_json::json!({ "reindexed": reindexed }))
+                .await;
+        });
+
+        Ok(task_id)
+    }
+}
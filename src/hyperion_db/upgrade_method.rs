@@ -0,0 +1,25 @@
+use super::hyperion_db_struct::HyperionDB;
+use crate::storage::{save_shard_to_disk, shard_format_version, CURRENT_FORMAT_VERSION};
+use std::error::Error;
+
+impl HyperionDB {
+    /// Rewrites `shard_id`'s file to the current on-disk format version if
+    /// it isn't already there. The shard is already loaded in memory (older
+    /// formats are transparently read by `load_shard_from_disk`), so an
+    /// upgrade is just a re-save. Returns the version the shard was upgraded
+    /// from, or `None` if it was already current.
+    pub async fn upgrade_shard(&self, shard_id: u32) -> Result<Option<u8>, Box<dyn Error + Send + Sync>> {
+        let data_dir = &self.shard_manager.data_dir;
+        let current = shard_format_version(data_dir, shard_id).await?;
+
+        match current {
+            Some(version) if version < CURRENT_FORMAT_VERSION => {
+                if let Some(shard) = self.shards.get(&shard_id) {
+                    save_shard_to_disk(data_dir, shard_id, shard.clone()).await?;
+                }
+                Ok(Some(version))
+            }
+            _ => Ok(None),
+        }
+    }
+}
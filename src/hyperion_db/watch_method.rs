@@ -0,0 +1,74 @@
+use super::hyperion_db_struct::HyperionDB;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::time::Instant;
+
+impl HyperionDB {
+    /// Blocks until `key` changes (relative to `since_seq`) or `timeout`
+    /// elapses, then returns its current value (`None` if absent/deleted)
+    /// alongside the sequence number to pass as `since_seq` on the next
+    /// call. If a change already happened at or before the subscribe --
+    /// i.e. `since_seq` is behind `change_feed`'s current sequence -- this
+    /// returns immediately instead of waiting for a new one, so a waiter
+    /// can never miss an edit that raced its `WATCH` call.
+    pub async fn watch_key(&self, key: &str, since_seq: u64, timeout: Duration) -> (Option<Value>, u64) {
+        // Subscribe before sampling the sequence: a change that lands in
+        // between is then either counted in `seq_at_start` or delivered on `rx`.
+        let shard_id = self.shard_manager.get_shard(key);
+        let rx = self.change_feed.subscribe_shard(shard_id);
+        let seq_at_start = self.change_feed.current_seq();
+        if seq_at_start > since_seq {
+            return (self.get(key).await, seq_at_start);
+        }
+
+        let Some(mut rx) = rx else {
+            return (self.get(key).await, seq_at_start);
+        };
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return (self.get(key).await, seq_at_start);
+            }
+
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Ok(event)) if event.key == key && event.seq > since_seq => {
+                    return (self.get(key).await, event.seq);
+                }
+                Ok(Ok(_other_key)) => continue,
+                Ok(Err(_lagged_or_closed)) => return (self.get(key).await, seq_at_start),
+                Err(_elapsed) => return (self.get(key).await, seq_at_start),
+            }
+        }
+    }
+
+    /// Blocks until a `field operator value` condition's matches change
+    /// (relative to `since_seq`) or `timeout` elapses, then returns the
+    /// current matches alongside the sequence number to pass as
+    /// `since_seq` on the next call. Re-evaluates the full condition on
+    /// every wakeup rather than trying to track which keys it affects --
+    /// simple, and cheap enough since it only runs once per change, not
+    /// per poll.
+    pub async fn watch_query(
+        &self,
+        field: &str,
+        operator: &str,
+        value: &str,
+        since_seq: u64,
+        timeout: Duration,
+    ) -> (Vec<Value>, u64) {
+        let mut rx = self.change_feed.subscribe_all();
+        let seq_at_start = self.change_feed.current_seq();
+        if seq_at_start > since_seq {
+            return (self.query_condition(field, operator, value).await, seq_at_start);
+        }
+
+        match tokio::time::timeout(timeout, rx.recv()).await {
+            Ok(Ok(event)) => (self.query_condition(field, operator, value).await, event.seq),
+            Ok(Err(_lagged_or_closed)) | Err(_elapsed) => {
+                (self.query_condition(field, operator, value).await, seq_at_start)
+            }
+        }
+    }
+}
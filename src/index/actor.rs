@@ -0,0 +1,373 @@
+use super::{text, update_indices_on_delete, update_indices_on_insert, Index, IndexStats};
+use crate::config::IndexedField;
+use dashmap::DashMap;
+use serde_json::Value;
+use std::collections::HashSet;
+use tokio::sync::{mpsc, oneshot};
+
+/// One message a shard's index actor understands. Mutations carry an owned
+/// snapshot of `indexed_fields` since the actor runs in its own task and
+/// can't borrow across the channel the way a direct `&DashMap` call could.
+enum IndexCommand {
+    Insert { key: String, value: Value, indexed_fields: Vec<IndexedField> },
+    Delete { key: String, value: Value, indexed_fields: Vec<IndexedField> },
+    Clear,
+    RemoveField { field: String },
+    FieldExists { field: String, reply: oneshot::Sender<bool> },
+    Query { field: String, operator: String, value: String, reply: oneshot::Sender<HashSet<String>> },
+    Stats { field: String, reply: oneshot::Sender<Option<IndexStats>> },
+    CountForValue { field: String, value: String, reply: oneshot::Sender<usize> },
+    Search { field: String, query: String, reply: oneshot::Sender<Vec<(String, usize)>> },
+    MatchQuery { field: String, query: String, reply: oneshot::Sender<Vec<(String, f64)>> },
+}
+
+/// A handle to one shard's index actor task. The task owns a private
+/// `DashMap<String, Index>` that only it ever touches, so callers never
+/// block on a lock -- they hand the actor a message and wait for its reply.
+#[derive(Clone)]
+pub struct IndexActorHandle {
+    sender: mpsc::Sender<IndexCommand>,
+}
+
+impl IndexActorHandle {
+    pub fn spawn() -> Self {
+        let (sender, mut receiver) = mpsc::channel(1024);
+        tokio::spawn(async move {
+            let indices: DashMap<String, Index> = DashMap::new();
+            while let Some(command) = receiver.recv().await {
+                match command {
+                    IndexCommand::Insert { key, value, indexed_fields } => {
+                        update_indices_on_insert(&indices, &key, &value, &indexed_fields).await;
+                    }
+                    IndexCommand::Delete { key, value, indexed_fields } => {
+                        update_indices_on_delete(&indices, &key, &value, &indexed_fields).await;
+                    }
+                    IndexCommand::Clear => indices.clear(),
+                    IndexCommand::RemoveField { field } => {
+                        indices.remove(&field);
+                    }
+                    IndexCommand::FieldExists { field, reply } => {
+                        let _ = reply.send(indices.contains_key(&field));
+                    }
+                    IndexCommand::Query { field, operator, value, reply } => {
+                        let result = indices
+                            .get(&field)
+                            .map(|index| index.query_keys(&operator, &value))
+                            .unwrap_or_default();
+                        let _ = reply.send(result);
+                    }
+                    IndexCommand::Stats { field, reply } => {
+                        let result = indices.get(&field).and_then(|index| index.stats());
+                        let _ = reply.send(result);
+                    }
+                    IndexCommand::CountForValue { field, value, reply } => {
+                        let result = indices.get(&field).map(|index| index.count_for_value(&value)).unwrap_or(0);
+                        let _ = reply.send(result);
+                    }
+                    IndexCommand::Search { field, query, reply } => {
+                        let result = indices
+                            .get(&field)
+                            .map(|index| match &*index {
+                                Index::FullText(inverted) => text::search(inverted, &query),
+                                _ => Vec::new(),
+                            })
+                            .unwrap_or_default();
+                        let _ = reply.send(result);
+                    }
+                    IndexCommand::MatchQuery { field, query, reply } => {
+                        let result = indices
+                            .get(&field)
+                            .map(|index| match &*index {
+                                Index::FullText(inverted) => text::bm25_search(inverted, &query),
+                                _ => Vec::new(),
+                            })
+                            .unwrap_or_default();
+                        let _ = reply.send(result);
+                    }
+                }
+            }
+        });
+        IndexActorHandle { sender }
+    }
+
+    pub async fn insert(&self, key: String, value: Value, indexed_fields: Vec<IndexedField>) {
+        let _ = self.sender.send(IndexCommand::Insert { key, value, indexed_fields }).await;
+    }
+
+    pub async fn delete(&self, key: String, value: Value, indexed_fields: Vec<IndexedField>) {
+        let _ = self.sender.send(IndexCommand::Delete { key, value, indexed_fields }).await;
+    }
+
+    pub async fn clear(&self) {
+        let _ = self.sender.send(IndexCommand::Clear).await;
+    }
+
+    pub async fn remove_field(&self, field: String) {
+        let _ = self.sender.send(IndexCommand::RemoveField { field }).await;
+    }
+
+    pub async fn field_exists(&self, field: String) -> bool {
+        let (reply, receiver) = oneshot::channel();
+        if self.sender.send(IndexCommand::FieldExists { field, reply }).await.is_err() {
+            return false;
+        }
+        receiver.await.unwrap_or(false)
+    }
+
+    pub async fn query(&self, field: String, operator: String, value: String) -> HashSet<String> {
+        let (reply, receiver) = oneshot::channel();
+        if self.sender.send(IndexCommand::Query { field, operator, value, reply }).await.is_err() {
+            return HashSet::new();
+        }
+        receiver.await.unwrap_or_default()
+    }
+
+    pub async fn stats(&self, field: String) -> Option<IndexStats> {
+        let (reply, receiver) = oneshot::channel();
+        if self.sender.send(IndexCommand::Stats { field, reply }).await.is_err() {
+            return None;
+        }
+        receiver.await.ok().flatten()
+    }
+
+    pub async fn count_for_value(&self, field: String, value: String) -> usize {
+        let (reply, receiver) = oneshot::channel();
+        if self.sender.send(IndexCommand::CountForValue { field, value, reply }).await.is_err() {
+            return 0;
+        }
+        receiver.await.unwrap_or(0)
+    }
+
+    pub async fn search(&self, field: String, query: String) -> Vec<(String, usize)> {
+        let (reply, receiver) = oneshot::channel();
+        if self.sender.send(IndexCommand::Search { field, query, reply }).await.is_err() {
+            return Vec::new();
+        }
+        receiver.await.unwrap_or_default()
+    }
+
+    pub async fn match_query(&self, field: String, query: String) -> Vec<(String, f64)> {
+        let (reply, receiver) = oneshot::channel();
+        if self.sender.send(IndexCommand::MatchQuery { field, query, reply }).await.is_err() {
+            return Vec::new();
+        }
+        receiver.await.unwrap_or_default()
+    }
+}
+
+/// One `IndexActorHandle` per record shard, replacing the single
+/// process-wide `DashMap<String, Index>` every write and query used to
+/// share. `shard_id` is the same stable hash `ShardManager::get_shard`
+/// already uses to place a record, so a key's index entries live on the
+/// exact actor that also owns that key's record shard.
+///
+/// Writes (`insert_into`/`delete_from`) go straight to the owning shard, so
+/// two concurrent writes to different shards never wait on each other.
+/// Queries have no single owning shard -- a field's values are scattered
+/// across every shard by key hash, not by value -- so they fan out to all
+/// shards concurrently and merge the partial answers.
+pub struct IndexShards {
+    shards: Vec<IndexActorHandle>,
+}
+
+impl IndexShards {
+    pub fn new(num_shards: u32) -> Self {
+        IndexShards {
+            shards: (0..num_shards).map(|_| IndexActorHandle::spawn()).collect(),
+        }
+    }
+
+    pub async fn insert_into(&self, shard_id: u32, key: String, value: Value, indexed_fields: Vec<IndexedField>) {
+        self.shards[shard_id as usize].insert(key, value, indexed_fields).await;
+    }
+
+    pub async fn delete_from(&self, shard_id: u32, key: String, value: Value, indexed_fields: Vec<IndexedField>) {
+        self.shards[shard_id as usize].delete(key, value, indexed_fields).await;
+    }
+
+    pub async fn clear(&self) {
+        for shard in &self.shards {
+            shard.clear().await;
+        }
+    }
+
+    pub async fn remove_field(&self, field: &str) {
+        for shard in &self.shards {
+            shard.remove_field(field.to_string()).await;
+        }
+    }
+
+    /// Scatters `field operator value` to every shard concurrently and
+    /// unions the matching keys.
+    pub async fn query(&self, field: &str, operator: &str, value: &str) -> HashSet<String> {
+        let handles: Vec<_> = self
+            .shards
+            .iter()
+            .cloned()
+            .map(|shard| {
+                let field = field.to_string();
+                let operator = operator.to_string();
+                let value = value.to_string();
+                tokio::spawn(async move { shard.query(field, operator, value).await })
+            })
+            .collect();
+
+        let mut merged = HashSet::new();
+        for handle in handles {
+            if let Ok(partial) = handle.await {
+                merged.extend(partial);
+            }
+        }
+        merged
+    }
+
+    /// Merges each shard's `IndexStats` into one: per-value counts are
+    /// summed (the same value can be held by keys hashed to different
+    /// shards), and `cardinality`/`min`/`max` are recomputed over that
+    /// merged set rather than any single shard's view.
+    pub async fn stats(&self, field: &str) -> Option<IndexStats> {
+        let handles: Vec<_> = self
+            .shards
+            .iter()
+            .cloned()
+            .map(|shard| {
+                let field = field.to_string();
+                tokio::spawn(async move { shard.stats(field).await })
+            })
+            .collect();
+
+        let mut merged_counts: Vec<(Value, usize)> = Vec::new();
+        let mut total_keys = 0;
+        for handle in handles {
+            let Ok(Some(partial)) = handle.await else { continue };
+            total_keys += partial.total_keys;
+            for (value, count) in partial.counts {
+                match merged_counts.iter_mut().find(|(v, _)| values_equal(v, &value)) {
+                    Some((_, existing)) => *existing += count,
+                    None => merged_counts.push((value, count)),
+                }
+            }
+        }
+        if merged_counts.is_empty() {
+            return None;
+        }
+        merged_counts.sort_by(compare_value_pairs);
+        Some(IndexStats {
+            cardinality: merged_counts.len(),
+            total_keys,
+            min: merged_counts.first().map(|(v, _)| v.clone()),
+            max: merged_counts.last().map(|(v, _)| v.clone()),
+            counts: merged_counts,
+        })
+    }
+
+    /// `None` if `field` isn't indexed on any shard, distinguishing that
+    /// from a genuine zero count for a value no key currently holds.
+    pub async fn count_for_value(&self, field: &str, value: &str) -> Option<usize> {
+        if !self.field_exists(field).await {
+            return None;
+        }
+
+        let handles: Vec<_> = self
+            .shards
+            .iter()
+            .cloned()
+            .map(|shard| {
+                let field = field.to_string();
+                let value = value.to_string();
+                tokio::spawn(async move { shard.count_for_value(field, value).await })
+            })
+            .collect();
+
+        let mut total = 0;
+        for handle in handles {
+            total += handle.await.unwrap_or(0);
+        }
+        Some(total)
+    }
+
+    /// Whether any shard currently has an index entry for `field`.
+    pub async fn field_exists(&self, field: &str) -> bool {
+        let handles: Vec<_> = self
+            .shards
+            .iter()
+            .cloned()
+            .map(|shard| {
+                let field = field.to_string();
+                tokio::spawn(async move { shard.field_exists(field).await })
+            })
+            .collect();
+
+        for handle in handles {
+            if handle.await.unwrap_or(false) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Fans `search` out to every shard and concatenates the results. No
+    /// per-key merge is needed -- unlike values, a given record key lives on
+    /// exactly one shard -- so this only needs to re-sort the concatenated
+    /// hits by descending score.
+    pub async fn search(&self, field: &str, query: &str) -> Vec<(String, usize)> {
+        let handles: Vec<_> = self
+            .shards
+            .iter()
+            .cloned()
+            .map(|shard| {
+                let field = field.to_string();
+                let query = query.to_string();
+                tokio::spawn(async move { shard.search(field, query).await })
+            })
+            .collect();
+
+        let mut merged = Vec::new();
+        for handle in handles {
+            if let Ok(partial) = handle.await {
+                merged.extend(partial);
+            }
+        }
+        merged.sort_by(|a, b| b.1.cmp(&a.1));
+        merged
+    }
+
+    /// Like `search`, but for `bm25_search`. Note each shard only sees its
+    /// own slice of the postings, so `idf` is computed against a per-shard
+    /// document count rather than the whole field's -- the same tradeoff
+    /// distributed search engines (Elasticsearch, the MeiliSearch design
+    /// this actor split is modeled on) make for per-shard relevance scoring.
+    pub async fn match_query(&self, field: &str, query: &str) -> Vec<(String, f64)> {
+        let handles: Vec<_> = self
+            .shards
+            .iter()
+            .cloned()
+            .map(|shard| {
+                let field = field.to_string();
+                let query = query.to_string();
+                tokio::spawn(async move { shard.match_query(field, query).await })
+            })
+            .collect();
+
+        let mut merged = Vec::new();
+        for handle in handles {
+            if let Ok(partial) = handle.await {
+                merged.extend(partial);
+            }
+        }
+        merged.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        merged
+    }
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    a.to_string() == b.to_string()
+}
+
+fn compare_value_pairs(a: &(Value, usize), b: &(Value, usize)) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    match (a.0.as_f64(), b.0.as_f64()) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.0.to_string().cmp(&b.0.to_string()),
+    }
+}
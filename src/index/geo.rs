@@ -0,0 +1,119 @@
+/// Bits of precision kept per axis when quantizing a coordinate into the
+/// Morton (Z-order) code stored as an `Index::Geo` cell key -- `u32` gives
+/// sub-centimeter resolution (about 9 mm of longitude at the equator and
+/// 5 mm of latitude per step).
+const AXIS_BITS: u32 = 32;
+
+/// The coarse grid level used to decompose a bounding box into a handful of
+/// contiguous Morton ranges (see `bbox_ranges`). Lower = fewer, larger
+/// quadrants to scan per query; higher = tighter candidate sets at the cost
+/// of iterating more quadrants for a large bbox.
+const QUADRANT_BITS: u32 = 6;
+
+fn quantize_lon(lon: f64) -> u32 {
+    let clamped = lon.clamp(-180.0, 180.0);
+    (((clamped + 180.0) / 360.0) * (u32::MAX as f64)) as u32
+}
+
+fn quantize_lat(lat: f64) -> u32 {
+    let clamped = lat.clamp(-90.0, 90.0);
+    (((clamped + 90.0) / 180.0) * (u32::MAX as f64)) as u32
+}
+
+/// Spreads `x`'s 32 bits out so there's a zero between each one, e.g.
+/// `0b1011 -> 0b01000101`. Interleaving the spread lon and lat bits (lon in
+/// the low position of each pair) produces the Morton code: coordinates
+/// close in 2D space end up numerically close as a single `u64`, which is
+/// what lets `BTreeMap::range` answer a spatial query with an ordered scan.
+fn spread_bits(x: u32) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 16)) & 0x0000FFFF0000FFFF;
+    x = (x | (x << 8)) & 0x00FF00FF00FF00FF;
+    x = (x | (x << 4)) & 0x0F0F0F0F0F0F0F0F;
+    x = (x | (x << 2)) & 0x3333333333333333;
+    x = (x | (x << 1)) & 0x5555555555555555;
+    x
+}
+
+fn morton(lon_bits: u32, lat_bits: u32) -> u64 {
+    spread_bits(lon_bits) | (spread_bits(lat_bits) << 1)
+}
+
+/// Encodes a point into its `Index::Geo` cell key.
+pub fn encode(lon: f64, lat: f64) -> u64 {
+    morton(quantize_lon(lon), quantize_lat(lat))
+}
+
+/// Covers `(min_lon, min_lat)..=(max_lon, max_lat)` with a set of
+/// contiguous Morton-code ranges suitable for `BTreeMap::range`. Z-order
+/// codes aren't globally contiguous over an arbitrary rectangle, but a
+/// quadrant of the coarse `QUADRANT_BITS`-per-axis grid *is* always a
+/// contiguous range of the full-resolution code -- so this walks every
+/// coarse quadrant the bbox touches and emits that quadrant's full-res
+/// range. Candidates from these ranges still need an exact lat/lon filter
+/// to drop the corners of each quadrant that fall outside the bbox.
+pub fn bbox_ranges(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Vec<(u64, u64)> {
+    let shift = AXIS_BITS - QUADRANT_BITS;
+    let cx_min = quantize_lon(min_lon) >> shift;
+    let cx_max = quantize_lon(max_lon) >> shift;
+    let cy_min = quantize_lat(min_lat) >> shift;
+    let cy_max = quantize_lat(max_lat) >> shift;
+
+    let cell_span: u64 = 1 << (2 * shift as u64);
+    let mut ranges = Vec::new();
+
+    for cx in cx_min..=cx_max {
+        for cy in cy_min..=cy_max {
+            let quadrant_code = morton(cx, cy);
+            let lo = quadrant_code << (2 * shift);
+            let hi = lo + (cell_span - 1);
+            ranges.push((lo, hi));
+        }
+    }
+
+    ranges
+}
+
+/// Great-circle distance between two lat/lon points, in meters.
+pub fn haversine_meters(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+    let (lat1_r, lat2_r) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+
+    let a = (d_lat / 2.0).sin().powi(2) + lat1_r.cos() * lat2_r.cos() * (d_lon / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().asin();
+    EARTH_RADIUS_M * c
+}
+
+/// Reads a point out of a field's stored value: either `[lon, lat]` or an
+/// object with `lat`/`lon` members. Used both when indexing a record
+/// (`update_indices_on_insert`) and when re-checking a geo candidate's
+/// exact position after a coarse `BTreeMap` range scan.
+pub fn point_from_value(value: &serde_json::Value) -> Option<(f64, f64)> {
+    match value {
+        serde_json::Value::Array(coords) => {
+            let lon = coords.get(0)?.as_f64()?;
+            let lat = coords.get(1)?.as_f64()?;
+            Some((lon, lat))
+        }
+        serde_json::Value::Object(obj) => {
+            let lon = obj.get("lon").and_then(|v| v.as_f64())?;
+            let lat = obj.get("lat").and_then(|v| v.as_f64())?;
+            Some((lon, lat))
+        }
+        _ => None,
+    }
+}
+
+/// A bounding box covering a `radius_meters` circle around `(lon, lat)`,
+/// used as the coarse candidate filter before the exact haversine check.
+/// One degree of latitude is always ~111.32km; longitude shrinks with
+/// `cos(latitude)`, so the box is widened accordingly near the poles.
+pub fn bbox_for_radius(lon: f64, lat: f64, radius_meters: f64) -> (f64, f64, f64, f64) {
+    const METERS_PER_DEGREE_LAT: f64 = 111_320.0;
+    let lat_delta = radius_meters / METERS_PER_DEGREE_LAT;
+    let lon_delta = radius_meters / (METERS_PER_DEGREE_LAT * lat.to_radians().cos().max(0.000001));
+
+    (lon - lon_delta, lat - lat_delta, lon + lon_delta, lat + lat_delta)
+}
@@ -1,16 +1,69 @@
+pub mod actor;
+pub mod geo;
+pub mod text;
+
 use serde_json::Value;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use dashmap::DashMap;
 use crate::config::{IndexedField, IndexType};
+use crate::error::HyperionError;
+
+/// Operators `query_numeric`/`Index::query_keys`'s `Numeric` branch understands.
+const NUMERIC_OPERATORS: &[&str] = &["=", "!=", ">", ">=", "<", "<=", "RANGE", "BETWEEN", "IN"];
+/// Operators `query_string`/`Index::query_keys`'s `String` branch understands.
+const STRING_OPERATORS: &[&str] =
+    &["=", "!=", "CONTAINS", "PREFIX", "STARTSWITH", "ENDSWITH", ">", ">=", "<", "<=", "RANGE", "BETWEEN", "IN"];
+/// Operators `Index::query_keys`'s `FullText` branch understands.
+const FULLTEXT_OPERATORS: &[&str] = &["MATCH"];
+/// Operators `Index::query_keys`'s `Geo` branch understands.
+const GEO_OPERATORS: &[&str] = &["WITHIN_BBOX", "NEAR"];
+
+/// Checks that `operator` is one `field`'s configured `index_type` actually
+/// supports, so a `QUERY` condition fails loudly instead of the index
+/// actors silently matching nothing. An operator this repo doesn't
+/// recognize under any index type is `InvalidQueryOperator`; one that's
+/// only valid under a *different* type (e.g. `>` against a `FullText`
+/// field) is the more specific `TypeMismatch`.
+pub fn validate_operator(field: &str, index_type: &IndexType, operator: &str) -> Result<(), HyperionError> {
+    let (allowed, expected) = match index_type {
+        IndexType::Numeric => (NUMERIC_OPERATORS, "Numeric"),
+        IndexType::String => (STRING_OPERATORS, "String"),
+        IndexType::FullText => (FULLTEXT_OPERATORS, "FullText"),
+        IndexType::Geo => (GEO_OPERATORS, "Geo"),
+    };
+    if allowed.contains(&operator) {
+        return Ok(());
+    }
+    let known_elsewhere = [NUMERIC_OPERATORS, STRING_OPERATORS, FULLTEXT_OPERATORS, GEO_OPERATORS]
+        .iter()
+        .any(|ops| ops.contains(&operator));
+    if known_elsewhere {
+        Err(HyperionError::TypeMismatch { field: field.to_string(), expected })
+    } else {
+        Err(HyperionError::InvalidQueryOperator { operator: operator.to_string(), field: field.to_string() })
+    }
+}
 
 /// Representa los tipos de índices en la base de datos.
 pub enum Index {
-    Numeric(BTreeMap<i64, HashSet<String>>),
+    /// Keyed on `encode_f64_ordered(value)` rather than the raw `f64` (which
+    /// isn't `Ord`), so a `BTreeMap` range scan still walks keys in true
+    /// numeric order.
+    Numeric(BTreeMap<u64, HashSet<String>>),
     String(BTreeMap<String, HashSet<String>>),
+    /// Inverted index mapping normalized tokens to the keys of records
+    /// containing them plus that token's term frequency within each key, so
+    /// `text::search` can do count-based ranking and `text::bm25_search`
+    /// (the `MATCH` command) can weigh matches by tf-idf.
+    FullText(HashMap<String, HashMap<String, u32>>),
+    /// Maps each indexed point's `geo::encode` Morton cell to the keys of
+    /// records at that cell, so spatially-near points land at numerically
+    /// adjacent `BTreeMap` keys. Backs `WITHIN_BBOX`/`NEAR`.
+    Geo(BTreeMap<u64, HashSet<String>>),
 }
 
 impl Index {
-    pub fn as_numeric_mut(&mut self) -> Option<&mut BTreeMap<i64, HashSet<String>>> {
+    pub fn as_numeric_mut(&mut self) -> Option<&mut BTreeMap<u64, HashSet<String>>> {
         if let Index::Numeric(ref mut map) = self {
             Some(map)
         } else {
@@ -25,22 +78,222 @@ impl Index {
             None
         }
     }
+
+    pub fn as_fulltext_mut(&mut self) -> Option<&mut HashMap<String, HashMap<String, u32>>> {
+        if let Index::FullText(ref mut map) = self {
+            Some(map)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_geo_mut(&mut self) -> Option<&mut BTreeMap<u64, HashSet<String>>> {
+        if let Index::Geo(ref mut map) = self {
+            Some(map)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the candidate keys for `operator`/`value`. For `Geo`, these
+    /// are only the cell-level candidates -- every key whose Morton cell
+    /// falls in one of the bbox's covering ranges -- and still need an
+    /// exact lat/lon (and, for `NEAR`, haversine distance) filter against
+    /// the actual record, which `query_condition` applies afterward since
+    /// that's the first point in the call chain with record access.
     pub fn query_keys(&self, operator: &str, value: &str) -> HashSet<String> {
         match self {
             Index::Numeric(btree_map) => query_numeric(btree_map, operator, value),
             Index::String(btree_map) => query_string(btree_map, operator, value),
+            Index::FullText(inverted) => {
+                if operator != "MATCH" {
+                    return HashSet::new();
+                }
+                text::search(inverted, value).into_iter().map(|(key, _)| key).collect()
+            }
+            Index::Geo(cells) => {
+                let ranges = match operator {
+                    "WITHIN_BBOX" => {
+                        let parts: Vec<f64> = value.split(',').filter_map(|p| p.trim().parse().ok()).collect();
+                        let [min_lon, min_lat, max_lon, max_lat] = parts[..] else {
+                            return HashSet::new();
+                        };
+                        geo::bbox_ranges(min_lon, min_lat, max_lon, max_lat)
+                    }
+                    "NEAR" => {
+                        let parts: Vec<f64> = value.split(',').filter_map(|p| p.trim().parse().ok()).collect();
+                        let [lon, lat, radius_m] = parts[..] else {
+                            return HashSet::new();
+                        };
+                        let (min_lon, min_lat, max_lon, max_lat) = geo::bbox_for_radius(lon, lat, radius_m);
+                        geo::bbox_ranges(min_lon, min_lat, max_lon, max_lat)
+                    }
+                    _ => return HashSet::new(),
+                };
+
+                let mut result = HashSet::new();
+                for (lo, hi) in ranges {
+                    for (_, keys) in cells.range(lo..=hi) {
+                        result.extend(keys.clone());
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    /// Per-value key counts backing `INDEX_STATS`, for faceted-browse UIs
+    /// and spotting skew before running a heavier query. `None` for
+    /// `FullText`, whose per-token posting lists aren't the same kind of
+    /// "distinct value" a numeric or string index reports, and for `Geo`.
+    pub fn stats(&self) -> Option<IndexStats> {
+        match self {
+            Index::Numeric(btree_map) => {
+                let counts: Vec<(Value, usize)> = btree_map
+                    .iter()
+                    .map(|(encoded, keys)| (decode_f64_ordered(*encoded), keys.len()))
+                    .map(|(value, count)| (Value::from(value), count))
+                    .collect();
+                Some(IndexStats {
+                    cardinality: btree_map.len(),
+                    total_keys: btree_map.values().map(|keys| keys.len()).sum(),
+                    min: counts.first().map(|(value, _)| value.clone()),
+                    max: counts.last().map(|(value, _)| value.clone()),
+                    counts,
+                })
+            }
+            Index::String(btree_map) => {
+                let counts: Vec<(Value, usize)> = btree_map
+                    .iter()
+                    .map(|(value, keys)| (Value::from(value.clone()), keys.len()))
+                    .collect();
+                Some(IndexStats {
+                    cardinality: btree_map.len(),
+                    total_keys: btree_map.values().map(|keys| keys.len()).sum(),
+                    min: counts.first().map(|(value, _)| value.clone()),
+                    max: counts.last().map(|(value, _)| value.clone()),
+                    counts,
+                })
+            }
+            Index::FullText(_) => None,
+            Index::Geo(_) => None,
+        }
+    }
+
+    /// The number of keys indexed under exactly `value`, or `0` if `value`
+    /// isn't present. Backs `STATS <field> <value>`, which answers off the
+    /// same `BTreeMap`/`HashSet` structures as `stats()` without building
+    /// the full per-value breakdown.
+    pub fn count_for_value(&self, value: &str) -> usize {
+        match self {
+            Index::Numeric(btree_map) => value
+                .parse::<f64>()
+                .ok()
+                .and_then(|v| btree_map.get(&encode_f64_ordered(v)))
+                .map_or(0, |keys| keys.len()),
+            Index::String(btree_map) => btree_map.get(value).map_or(0, |keys| keys.len()),
+            Index::FullText(inverted) => inverted.get(value).map_or(0, |postings| postings.len()),
+            Index::Geo(_) => 0,
         }
     }
 }
 
+/// Result of `Index::stats`: the number of distinct values the index holds
+/// (`cardinality`), the total number of keys indexed across all values
+/// (`total_keys`), the lexicographically/numerically smallest and largest
+/// indexed values (`min`/`max`), and the per-value breakdown (`counts`).
+pub struct IndexStats {
+    pub cardinality: usize,
+    pub total_keys: usize,
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+    pub counts: Vec<(Value, usize)>,
+}
+
+/// Encodes `v` as a `u64` whose unsigned ordering matches `v`'s numeric
+/// ordering, so `Index::Numeric` can key a `BTreeMap` on the full, exact
+/// `f64` domain instead of a `(v * 1000.0) as i64` fixed-point approximation
+/// that silently collapsed close values and overflowed/saturated on large
+/// magnitudes. IEEE-754 bit patterns already sort correctly within a sign,
+/// so flipping the sign bit of a positive number moves it above all
+/// negatives, and inverting every bit of a negative number reverses its
+/// (otherwise backwards) order while keeping it below all positives.
+/// `NaN` has no numeric ordering, so callers must check `is_nan()` before
+/// indexing a value and skip it.
+fn encode_f64_ordered(v: f64) -> u64 {
+    let bits = v.to_bits();
+    if v.is_sign_negative() {
+        !bits
+    } else {
+        bits | (1u64 << 63)
+    }
+}
+
+/// Inverse of `encode_f64_ordered`, used to recover the original value for
+/// `IndexStats`' `min`/`max`/per-value breakdown.
+fn decode_f64_ordered(encoded: u64) -> f64 {
+    let bits = if encoded & (1u64 << 63) != 0 {
+        encoded & !(1u64 << 63)
+    } else {
+        !encoded
+    };
+    f64::from_bits(bits)
+}
+
+/// Converts `"min:max"` into a parsed `(min, max)` pair, used by the `RANGE` operator.
+fn parse_range(value: &str) -> Option<(f64, f64)> {
+    let (min_str, max_str) = value.split_once(':')?;
+    Some((min_str.parse().ok()?, max_str.parse().ok()?))
+}
+
 /// Ejecuta una consulta en índices numéricos.
-fn query_numeric(map: &BTreeMap<i64, HashSet<String>>, operator: &str, value: &str) -> HashSet<String> {
+fn query_numeric(map: &BTreeMap<u64, HashSet<String>>, operator: &str, value: &str) -> HashSet<String> {
     let mut result = HashSet::new();
+
+    match operator {
+        "RANGE" => {
+            if let Some((min, max)) = parse_range(value) {
+                let min_value = encode_f64_ordered(min);
+                let max_value = encode_f64_ordered(max);
+                for (_, set) in map.range(min_value..=max_value) {
+                    result.extend(set.clone());
+                }
+            }
+            return result;
+        }
+        "BETWEEN" => {
+            // `value` is `"lo hi"`, space-joined by `parse_condition` to
+            // match `query_string`'s own BETWEEN format.
+            if let Some((lo, hi)) = value.split_once(' ') {
+                if let (Ok(lo), Ok(hi)) = (lo.parse::<f64>(), hi.parse::<f64>()) {
+                    let min_value = encode_f64_ordered(lo);
+                    let max_value = encode_f64_ordered(hi);
+                    for (_, set) in map.range(min_value..=max_value) {
+                        result.extend(set.clone());
+                    }
+                }
+            }
+            return result;
+        }
+        "IN" => {
+            for candidate in value.split(',') {
+                if let Ok(v) = candidate.trim().parse::<f64>() {
+                    let encoded = encode_f64_ordered(v);
+                    if let Some(set) = map.get(&encoded) {
+                        result.extend(set.clone());
+                    }
+                }
+            }
+            return result;
+        }
+        _ => {}
+    }
+
     if let Ok(v) = value.parse::<f64>() {
-        let int_value = (v * 1000.0) as i64; // Convertir a entero para el índice
+        let encoded = encode_f64_ordered(v);
         match operator {
             "=" => {
-                if let Some(set) = map.get(&int_value) {
+                if let Some(set) = map.get(&encoded) {
                     result.extend(set.clone());
                 }
             }
@@ -50,22 +303,22 @@ fn query_numeric(map: &BTreeMap<i64, HashSet<String>>, operator: &str, value: &s
                 }
             }
             ">" => {
-                for (_, set) in map.range((int_value + 1)..) {
+                for (_, set) in map.range((encoded + 1)..) {
                     result.extend(set.clone());
                 }
             }
             ">=" => {
-                for (_, set) in map.range(int_value..) {
+                for (_, set) in map.range(encoded..) {
                     result.extend(set.clone());
                 }
             }
             "<" => {
-                for (_, set) in map.range(..int_value) {
+                for (_, set) in map.range(..encoded) {
                     result.extend(set.clone());
                 }
             }
             "<=" => {
-                for (_, set) in map.range(..=int_value) {
+                for (_, set) in map.range(..=encoded) {
                     result.extend(set.clone());
                 }
             }
@@ -96,6 +349,63 @@ fn query_string(map: &BTreeMap<String, HashSet<String>>, operator: &str, value:
                 }
             }
         }
+        "PREFIX" | "STARTSWITH" => {
+            // BTreeMap is ordered, so matching keys form a contiguous range
+            // starting at `value` -- no need to scan entries outside it.
+            for (_, set) in map.range(value.to_string()..).take_while(|(k, _)| k.starts_with(value)) {
+                result.extend(set.clone());
+            }
+        }
+        "ENDSWITH" => {
+            // No ordering puts every suffix-matching key next to each
+            // other, so this still has to scan the whole index.
+            for (k, set) in map.iter() {
+                if k.ends_with(value) {
+                    result.extend(set.clone());
+                }
+            }
+        }
+        ">" => {
+            for (_, set) in map.range((std::ops::Bound::Excluded(value.to_string()), std::ops::Bound::Unbounded)) {
+                result.extend(set.clone());
+            }
+        }
+        ">=" => {
+            for (_, set) in map.range(value.to_string()..) {
+                result.extend(set.clone());
+            }
+        }
+        "<" => {
+            for (_, set) in map.range(..value.to_string()) {
+                result.extend(set.clone());
+            }
+        }
+        "<=" => {
+            for (_, set) in map.range(..=value.to_string()) {
+                result.extend(set.clone());
+            }
+        }
+        "RANGE" => {
+            if let Some((min, max)) = value.split_once(':') {
+                for (_, set) in map.range(min.to_string()..=max.to_string()) {
+                    result.extend(set.clone());
+                }
+            }
+        }
+        "BETWEEN" => {
+            if let Some((lo, hi)) = value.split_once(' ') {
+                for (_, set) in map.range(lo.to_string()..=hi.to_string()) {
+                    result.extend(set.clone());
+                }
+            }
+        }
+        "IN" => {
+            for candidate in value.split(',') {
+                if let Some(set) = map.get(candidate.trim()) {
+                    result.extend(set.clone());
+                }
+            }
+        }
         _ => {}
     }
     result
@@ -118,16 +428,18 @@ pub async fn update_indices_on_insert(
                     IndexType::Numeric => {
                         if let Value::Number(num) = field_value {
                             if let Some(n) = num.as_f64() {
-                                let int_value = (n * 1000.0) as i64; // Convertir a entero
-                                indices
-                                    .entry(field.clone())
-                                    .or_insert_with(|| Index::Numeric(BTreeMap::new()));
-                                if let Some(mut index) = indices.get_mut(field) {
-                                    if let Index::Numeric(ref mut btree_map) = *index {
-                                        btree_map
-                                            .entry(int_value)
-                                            .or_insert_with(HashSet::new)
-                                            .insert(key.clone());
+                                if !n.is_nan() {
+                                    let encoded = encode_f64_ordered(n);
+                                    indices
+                                        .entry(field.clone())
+                                        .or_insert_with(|| Index::Numeric(BTreeMap::new()));
+                                    if let Some(mut index) = indices.get_mut(field) {
+                                        if let Index::Numeric(ref mut btree_map) = *index {
+                                            btree_map
+                                                .entry(encoded)
+                                                .or_insert_with(HashSet::new)
+                                                .insert(key.clone());
+                                        }
                                     }
                                 }
                             }
@@ -148,6 +460,37 @@ pub async fn update_indices_on_insert(
                             }
                         }
                     }
+                    IndexType::FullText => {
+                        if let Value::String(s) = field_value {
+                            indices
+                                .entry(field.clone())
+                                .or_insert_with(|| Index::FullText(HashMap::new()));
+                            if let Some(mut index) = indices.get_mut(field) {
+                                if let Some(inverted) = index.as_fulltext_mut() {
+                                    for token in text::tokenize(s) {
+                                        *inverted
+                                            .entry(token)
+                                            .or_insert_with(HashMap::new)
+                                            .entry(key.clone())
+                                            .or_insert(0) += 1;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    IndexType::Geo => {
+                        if let Some((lon, lat)) = geo::point_from_value(field_value) {
+                            let cell = geo::encode(lon, lat);
+                            indices
+                                .entry(field.clone())
+                                .or_insert_with(|| Index::Geo(BTreeMap::new()));
+                            if let Some(mut index) = indices.get_mut(field) {
+                                if let Some(cells) = index.as_geo_mut() {
+                                    cells.entry(cell).or_insert_with(HashSet::new).insert(key.clone());
+                                }
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -170,17 +513,19 @@ pub async fn update_indices_on_delete(
                     IndexType::Numeric => {
                         if let Value::Number(num) = field_value {
                             if let Some(n) = num.as_f64() {
-                                let int_value = (n * 1000.0) as i64;
-                                if let Some(mut index) = indices.get_mut(field) {
-                                    if let Some(btree_map) = index.as_numeric_mut() {
-                                        if let Some(keys_set) = btree_map.get_mut(&int_value) {
-                                            keys_set.remove(key);
-                                            if keys_set.is_empty() {
-                                                btree_map.remove(&int_value);
+                                if !n.is_nan() {
+                                    let encoded = encode_f64_ordered(n);
+                                    if let Some(mut index) = indices.get_mut(field) {
+                                        if let Some(btree_map) = index.as_numeric_mut() {
+                                            if let Some(keys_set) = btree_map.get_mut(&encoded) {
+                                                keys_set.remove(key);
+                                                if keys_set.is_empty() {
+                                                    btree_map.remove(&encoded);
+                                                }
+                                            }
+                                            if btree_map.is_empty() {
+                                                indices.remove(field);
                                             }
-                                        }
-                                        if btree_map.is_empty() {
-                                            indices.remove(field);
                                         }
                                     }
                                 }
@@ -204,6 +549,43 @@ pub async fn update_indices_on_delete(
                             }
                         }
                     }
+                    IndexType::FullText => {
+                        if let Value::String(s) = field_value {
+                            if let Some(mut index) = indices.get_mut(field) {
+                                if let Some(inverted) = index.as_fulltext_mut() {
+                                    for token in text::tokenize(s) {
+                                        if let Some(postings) = inverted.get_mut(&token) {
+                                            postings.remove(key);
+                                            if postings.is_empty() {
+                                                inverted.remove(&token);
+                                            }
+                                        }
+                                    }
+                                    if inverted.is_empty() {
+                                        indices.remove(field);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    IndexType::Geo => {
+                        if let Some((lon, lat)) = geo::point_from_value(field_value) {
+                            let cell = geo::encode(lon, lat);
+                            if let Some(mut index) = indices.get_mut(field) {
+                                if let Some(cells) = index.as_geo_mut() {
+                                    if let Some(keys_set) = cells.get_mut(&cell) {
+                                        keys_set.remove(key);
+                                        if keys_set.is_empty() {
+                                            cells.remove(&cell);
+                                        }
+                                    }
+                                    if cells.is_empty() {
+                                        indices.remove(field);
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -212,7 +594,7 @@ pub async fn update_indices_on_delete(
 
 
 /// Obtiene el campo anidado según el índice.
-fn get_nested_field<'a>(map: &'a serde_json::Map<String, Value>, field: &str) -> Option<&'a Value> {
+pub(crate) fn get_nested_field<'a>(map: &'a serde_json::Map<String, Value>, field: &str) -> Option<&'a Value> {
     let parts: Vec<&str> = field.split('.').collect();
     let mut current = map;
     for part in parts.iter() {
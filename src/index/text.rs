@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+
+/// Splits `s` into lowercase, lightly-stemmed tokens on whitespace and
+/// punctuation, for use as keys into a full-text inverted index.
+pub fn tokenize(s: &str) -> Vec<String> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| stem(&token.to_lowercase()))
+        .collect()
+}
+
+/// Strips a handful of common suffixes so that e.g. "jumping"/"jumped"/"jumps"
+/// index under the same term. Deliberately simple -- a real stemmer (e.g.
+/// Porter) would be overkill for the perf harness's generated text fields.
+fn stem(token: &str) -> String {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if token.len() > suffix.len() + 2 && token.ends_with(suffix) {
+            return token[..token.len() - suffix.len()].to_string();
+        }
+    }
+    token.to_string()
+}
+
+/// Classic dynamic-programming Levenshtein (edit) distance between `a` and `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut prev_diag = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let temp = row[j];
+            row[j] = if a[i - 1] == b[j - 1] {
+                prev_diag
+            } else {
+                1 + prev_diag.min(row[j]).min(row[j - 1])
+            };
+            prev_diag = temp;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// A BK-tree (Burkhard-Keller tree) indexing terms by Levenshtein distance,
+/// so a mistyped query token can find the index terms within a small edit
+/// distance without scanning the whole term dictionary.
+struct BkTree {
+    root: Option<Box<BkNode>>,
+}
+
+struct BkNode {
+    term: String,
+    children: HashMap<usize, Box<BkNode>>,
+}
+
+impl BkTree {
+    fn new() -> Self {
+        BkTree { root: None }
+    }
+
+    fn insert(&mut self, term: &str) {
+        match &mut self.root {
+            None => self.root = Some(Box::new(BkNode { term: term.to_string(), children: HashMap::new() })),
+            Some(root) => Self::insert_node(root, term),
+        }
+    }
+
+    fn insert_node(node: &mut BkNode, term: &str) {
+        let distance = levenshtein(&node.term, term);
+        if distance == 0 {
+            return;
+        }
+        match node.children.get_mut(&distance) {
+            Some(child) => Self::insert_node(child, term),
+            None => {
+                node.children
+                    .insert(distance, Box::new(BkNode { term: term.to_string(), children: HashMap::new() }));
+            }
+        }
+    }
+
+    /// Returns every indexed term within `max_distance` of `query`, using
+    /// the triangle inequality to prune whole subtrees that can't contain a match.
+    fn find_within(&self, query: &str, max_distance: usize) -> Vec<String> {
+        let mut matches = Vec::new();
+        if let Some(root) = &self.root {
+            Self::search_node(root, query, max_distance, &mut matches);
+        }
+        matches
+    }
+
+    fn search_node(node: &BkNode, query: &str, max_distance: usize, matches: &mut Vec<String>) {
+        let distance = levenshtein(&node.term, query);
+        if distance <= max_distance {
+            matches.push(node.term.clone());
+        }
+
+        let lower = distance.saturating_sub(max_distance);
+        let upper = distance + max_distance;
+        for (child_distance, child) in &node.children {
+            if *child_distance >= lower && *child_distance <= upper {
+                Self::search_node(child, query, max_distance, matches);
+            }
+        }
+    }
+}
+
+/// The maximum edit distance tolerated for a query token of this length:
+/// short tokens must match exactly, longer ones tolerate one typo, and
+/// long ones tolerate two.
+fn max_edit_distance(token_len: usize) -> usize {
+    if token_len < 4 {
+        0
+    } else if token_len < 8 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Searches `index` (a token -> per-key term frequency inverted index) for
+/// `query`, tolerating typos per `max_edit_distance`. Each query token's
+/// postings are the union of every index term within tolerance (found via a
+/// BK-tree over the term dictionary); matching a record requires every query
+/// token to be present (AND semantics). Results are ranked by descending
+/// number of term matches contributing to that key, ties broken by key.
+pub fn search(index: &HashMap<String, HashMap<String, u32>>, query: &str) -> Vec<(String, usize)> {
+    let query_tokens = tokenize(query);
+    if query_tokens.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dictionary = BkTree::new();
+    for term in index.keys() {
+        dictionary.insert(term);
+    }
+
+    let mut scores: HashMap<String, usize> = HashMap::new();
+    let mut keys_per_token: Vec<HashSet<String>> = Vec::new();
+
+    for token in &query_tokens {
+        let candidate_terms = dictionary.find_within(token, max_edit_distance(token.len()));
+        let mut token_keys = HashSet::new();
+
+        for term in candidate_terms {
+            if let Some(postings) = index.get(&term) {
+                for key in postings.keys() {
+                    token_keys.insert(key.clone());
+                    *scores.entry(key.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        keys_per_token.push(token_keys);
+    }
+
+    let mut matched = keys_per_token[0].clone();
+    for token_keys in &keys_per_token[1..] {
+        matched = matched.intersection(token_keys).cloned().collect();
+    }
+
+    let mut ranked: Vec<(String, usize)> = matched
+        .into_iter()
+        .map(|key| {
+            let score = scores.get(&key).copied().unwrap_or(0);
+            (key, score)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+/// Searches `index` like `search`, but ranks matches by a BM25-style score
+/// (`sum over query terms of tf * idf`) instead of raw term-match count, so
+/// a key where a rarer query term appears many times outranks one that
+/// merely contains every term once. `idf` uses the classic
+/// `ln((N - df + 0.5) / (df + 0.5) + 1)` form, where `N` is the number of
+/// distinct keys anywhere in the index and `df` is how many of them contain
+/// the term; `tf` is the term's per-key frequency recorded by
+/// `update_indices_on_insert`. Backs the `MATCH` command.
+pub fn bm25_search(index: &HashMap<String, HashMap<String, u32>>, query: &str) -> Vec<(String, f64)> {
+    let query_tokens = tokenize(query);
+    if query_tokens.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dictionary = BkTree::new();
+    for term in index.keys() {
+        dictionary.insert(term);
+    }
+
+    let total_docs: HashSet<&String> = index.values().flat_map(|postings| postings.keys()).collect();
+    let n = total_docs.len() as f64;
+
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    let mut keys_per_token: Vec<HashSet<String>> = Vec::new();
+
+    for token in &query_tokens {
+        let candidate_terms = dictionary.find_within(token, max_edit_distance(token.len()));
+        let mut token_keys = HashSet::new();
+
+        for term in candidate_terms {
+            if let Some(postings) = index.get(&term) {
+                let df = postings.len() as f64;
+                let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+
+                for (key, tf) in postings {
+                    token_keys.insert(key.clone());
+                    *scores.entry(key.clone()).or_insert(0.0) += (*tf as f64) * idf;
+                }
+            }
+        }
+
+        keys_per_token.push(token_keys);
+    }
+
+    let mut matched = keys_per_token[0].clone();
+    for token_keys in &keys_per_token[1..] {
+        matched = matched.intersection(token_keys).cloned().collect();
+    }
+
+    let mut ranked: Vec<(String, f64)> = matched
+        .into_iter()
+        .map(|key| {
+            let score = scores.get(&key).copied().unwrap_or(0.0);
+            (key, score)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
@@ -10,12 +10,20 @@ use tokio::sync::Mutex;
 
 use tokio::io::{AsyncReadExt, AsyncBufReadExt, AsyncWriteExt, BufReader};
 
+mod change_feed;
 mod config;
+mod config_watcher;
+mod error;
+mod gateway;
 mod handler;
 mod hyperion_db;
 mod index;
+mod metrics;
+mod replication;
 mod shard_manager;
 mod storage;
+mod tasks;
+mod versioning;
 
 #[napi]
 pub struct HyperionDBWrapper {
@@ -47,6 +55,7 @@ impl HyperionDBWrapper {
         let index_type = match index_type.as_str() {
           "Numeric" => IndexType::Numeric,
           "String" => IndexType::String,
+          "FullText" => IndexType::FullText,
           _ => return Err(napi::Error::from_reason(format!("Invalid index type: {}", index_type))),
         };
         Ok(IndexedField { field, index_type })
@@ -57,6 +66,8 @@ impl HyperionDBWrapper {
       num_shards,
       data_dir,
       indexed_fields,
+      replication: None,
+      checkpoint_interval_secs: None,
     };
 
     let db = hyperion_db::HyperionDB::new(config)
@@ -124,6 +135,59 @@ impl HyperionDBWrapper {
     Ok(())
   }
 
+  /// Starts a WebSocket listener alongside the raw-TCP server, speaking the
+  /// exact same command protocol over text frames so browser and
+  /// tunneled clients that can't open a native socket can still use
+  /// HyperionDB.
+  #[napi]
+  pub async fn start_ws_server(&self, port: u16) -> Result<()> {
+    let db_lock = self.db.clone();
+    let address = format!("127.0.0.1:{}", port);
+    let listener = TcpListener::bind(&address).await
+      .map_err(|e| napi::Error::from_reason(format!("Error binding to address {}: {}", address, e)))?;
+
+    println!("HyperionDB WebSocket server running on {}", address);
+
+    tokio::spawn(async move {
+      loop {
+        let (socket, _) = match listener.accept().await {
+          Ok(connection) => connection,
+          Err(e) => {
+            eprintln!("Failed to accept WebSocket connection: {}", e);
+            continue;
+          }
+        };
+
+        let db_lock = db_lock.clone();
+        tokio::spawn(async move {
+          let mut socket = socket;
+          if let Err(e) = gateway::perform_handshake(&mut socket).await {
+            eprintln!("WebSocket handshake error: {}", e);
+            return;
+          }
+
+          while let Ok(Some(command)) = gateway::read_text_frame(&mut socket).await {
+            let db = db_lock.lock().await;
+            let db_ref = db.as_ref().expect("Database not initialized");
+
+            let response = match handle_command(db_ref, command).await {
+              Ok(resp) => resp,
+              Err(e) => format!("ERR {}\n", e),
+            };
+            drop(db);
+
+            if let Err(e) = gateway::write_text_frame(&mut socket, response.trim_end_matches('\n')).await {
+              eprintln!("Failed to write to WebSocket: {}", e);
+              break;
+            }
+          }
+        });
+      }
+    });
+
+    Ok(())
+  }
+
   #[napi]
   pub async fn query(&self, query_str: String) -> Result<String> {
     println!("Ejecutando query: {}", query_str);
@@ -1,13 +1,23 @@
+mod error;
 mod hyperion_db;
 mod handler;
 mod index;
 mod storage;
 mod config;
 mod shard_manager;
+mod replication;
+mod versioning;
+mod gateway;
+mod metrics;
+mod config_watcher;
+mod change_feed;
+mod tasks;
 
 use hyperion_db::HyperionDB;
 use config::Config;
-use handler::handle_command;
+use handler::{handle_command, is_replicated_write};
+use replication::{NotLeaderError, RaftNode};
+use gateway::serve_http;
 use tokio::net::TcpListener;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use std::error::Error;
@@ -17,15 +27,74 @@ use std::sync::Arc;
 async fn main() -> Result<(), Box<dyn Error>> {
     let config_path = "config.json";
     let config = Config::load_from_file(config_path)?;
+    let replication_config = config.replication.clone();
 
     let db = Arc::new(HyperionDB::new(config).await?);
 
+    // When clustered, `INSERT`/`INSERT_OR_UPDATE`/`DELETE` must go through
+    // `propose` (replicated log + quorum) instead of mutating this node's
+    // shards directly; `raft_node` is threaded into the TCP loop below so
+    // it can make that call per command.
+    let raft_node: Option<Arc<RaftNode>> = if let Some(repl) = replication_config {
+        let raft_node = Arc::new(RaftNode::new(repl.node_id, repl.peers, db.clone()));
+        let listen_addr = repl.listen_addr.clone();
+        {
+            let raft_node = raft_node.clone();
+            tokio::spawn(async move {
+                if let Err(e) = raft_node.serve(&listen_addr).await {
+                    eprintln!("Raft replication listener stopped: {}", e);
+                }
+            });
+        }
+        Some(raft_node)
+    } else {
+        None
+    };
+
+    {
+        let db = db.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_http(db, "127.0.0.1:8090").await {
+                eprintln!("HTTP gateway stopped: {}", e);
+            }
+        });
+    }
+
+    {
+        let db = db.clone();
+        tokio::spawn(config_watcher::watch(db, config_path.to_string()));
+    }
+
+    {
+        let db = db.clone();
+        tokio::spawn(async move {
+            let mut elapsed_secs: u64 = 0;
+            loop {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                elapsed_secs += 1;
+
+                let interval = *db.checkpoint_interval_secs.read().await;
+                if let Some(interval) = interval {
+                    if interval > 0 && elapsed_secs >= interval {
+                        elapsed_secs = 0;
+                        for shard_id in 0..db.shard_manager.num_shards {
+                            if let Err(e) = db.checkpoint_shard(shard_id).await {
+                                eprintln!("Scheduled checkpoint failed for shard {}: {}", shard_id, e);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+
     let listener = TcpListener::bind("127.0.0.1:8080").await?;
     println!("HyperionDB Server running on 127.0.0.1:8080");
 
     loop {
         let (socket, _) = listener.accept().await?;
         let db = db.clone();
+        let raft_node = raft_node.clone();
 
         tokio::spawn(async move {
             let (reader, mut writer) = socket.into_split();
@@ -37,9 +106,21 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     break;
                 }
 
-                let response = match handle_command(&db, line.trim().to_string()).await {
-                    Ok(resp) => resp,
-                    Err(e) => format!("ERR {}\n", e),
+                let command = line.trim().to_string();
+                let response = match &raft_node {
+                    Some(raft_node) if is_replicated_write(&command) => {
+                        match raft_node.propose(command).await {
+                            Ok(resp) => resp,
+                            Err(e) if e.downcast_ref::<NotLeaderError>().is_some() => {
+                                "ERR MOVED: this node is not the Raft leader for this cluster\n".to_string()
+                            }
+                            Err(e) => format!("ERR {}\n", e),
+                        }
+                    }
+                    _ => match handle_command(&db, command).await {
+                        Ok(resp) => resp,
+                        Err(e) => format!("ERR {}\n", e),
+                    },
                 };
 
                 if let Err(e) = writer.write_all(response.as_bytes()).await {
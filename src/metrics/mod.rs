@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// In-process counters instrumenting the command handlers, exposed over the
+/// HTTP gateway's `/metrics` endpoint in Prometheus exposition format.
+#[derive(Default)]
+pub struct Metrics {
+    pub inserts_total: AtomicU64,
+    pub deletes_total: AtomicU64,
+    pub gets_total: AtomicU64,
+    pub queries_total: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn inc_inserts(&self) {
+        self.inserts_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_deletes(&self) {
+        self.deletes_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_gets(&self) {
+        self.gets_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_queries(&self) {
+        self.queries_total.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Renders the global counters plus a per-shard record gauge in Prometheus
+/// text exposition format.
+pub fn render_prometheus(metrics: &Metrics, shard_sizes: &[(u32, usize)]) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP hyperiondb_inserts_total Total INSERT/INSERT_OR_UPDATE operations.\n");
+    out.push_str("# TYPE hyperiondb_inserts_total counter\n");
+    out.push_str(&format!(
+        "hyperiondb_inserts_total {}\n",
+        metrics.inserts_total.load(Ordering::Relaxed)
+    ));
+
+    out.push_str("# HELP hyperiondb_deletes_total Total DELETE operations.\n");
+    out.push_str("# TYPE hyperiondb_deletes_total counter\n");
+    out.push_str(&format!(
+        "hyperiondb_deletes_total {}\n",
+        metrics.deletes_total.load(Ordering::Relaxed)
+    ));
+
+    out.push_str("# HELP hyperiondb_gets_total Total GET operations.\n");
+    out.push_str("# TYPE hyperiondb_gets_total counter\n");
+    out.push_str(&format!(
+        "hyperiondb_gets_total {}\n",
+        metrics.gets_total.load(Ordering::Relaxed)
+    ));
+
+    out.push_str("# HELP hyperiondb_queries_total Total QUERY operations.\n");
+    out.push_str("# TYPE hyperiondb_queries_total counter\n");
+    out.push_str(&format!(
+        "hyperiondb_queries_total {}\n",
+        metrics.queries_total.load(Ordering::Relaxed)
+    ));
+
+    out.push_str("# HELP hyperiondb_shard_records Number of records currently held by a shard.\n");
+    out.push_str("# TYPE hyperiondb_shard_records gauge\n");
+    for (shard_id, size) in shard_sizes {
+        out.push_str(&format!("hyperiondb_shard_records{{shard=\"{}\"}} {}\n", shard_id, size));
+    }
+
+    out
+}
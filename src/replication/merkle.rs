@@ -0,0 +1,81 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// A Merkle tree built over a shard's key/value pairs, used to detect and
+/// repair divergence between replicas without transferring full shards.
+///
+/// Leaves are hashes of `key + serialized value`, sorted by key so that two
+/// replicas holding the same data always build an identical tree.
+pub struct MerkleTree {
+    /// Leaf hash for every key, keyed by key for quick diffing.
+    pub leaves: HashMap<String, String>,
+    root: String,
+}
+
+fn hash_hex(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+impl MerkleTree {
+    /// Builds a tree from `(key, serialized_value)` pairs.
+    pub fn build(entries: &[(String, String)]) -> Self {
+        let mut sorted: Vec<&(String, String)> = entries.iter().collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let leaves: HashMap<String, String> = sorted
+            .iter()
+            .map(|(key, value)| (key.clone(), hash_hex(&[key, value])))
+            .collect();
+
+        let mut level: Vec<String> = sorted.iter().map(|(key, _)| leaves[key].clone()).collect();
+        if level.is_empty() {
+            level.push(hash_hex(&["empty"]));
+        }
+
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_hex(&[a, b]),
+                    [a] => hash_hex(&[a, a]),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        MerkleTree {
+            leaves,
+            root: level.remove(0),
+        }
+    }
+
+    /// The root digest summarizing the entire shard's contents.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Returns the keys whose leaf hash differs (or is missing) between this
+    /// tree and `other_leaves`, i.e. the keys that need to be re-synced.
+    pub fn diff(&self, other_leaves: &HashMap<String, String>) -> Vec<String> {
+        let mut divergent = Vec::new();
+
+        for (key, hash) in &self.leaves {
+            if other_leaves.get(key) != Some(hash) {
+                divergent.push(key.clone());
+            }
+        }
+        for key in other_leaves.keys() {
+            if !self.leaves.contains_key(key) {
+                divergent.push(key.clone());
+            }
+        }
+
+        divergent.sort();
+        divergent.dedup();
+        divergent
+    }
+}
@@ -0,0 +1,5 @@
+pub mod merkle;
+pub mod raft;
+
+pub use merkle::MerkleTree;
+pub use raft::{NotLeaderError, RaftNode, Role};
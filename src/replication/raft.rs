@@ -0,0 +1,190 @@
+use crate::handler::handle_command;
+use crate::hyperion_db::HyperionDB;
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+
+/// Role a `RaftNode` can hold within the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Follower,
+    Candidate,
+    Leader,
+}
+
+/// A single replicated command, tagged with the term it was proposed in.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub term: u64,
+    pub command: String,
+}
+
+/// Returned by `propose` when a non-leader node receives a write, so the
+/// TCP front end in `main.rs` can turn it into a client-facing redirect
+/// instead of a generic `ERR`.
+#[derive(Debug)]
+pub struct NotLeaderError;
+
+impl std::fmt::Display for NotLeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not the leader")
+    }
+}
+
+impl std::error::Error for NotLeaderError {}
+
+/// Raft-based replication layer around a local `HyperionDB` instance.
+///
+/// Each `RaftNode` keeps its own replicated log and forwards committed
+/// entries to the underlying database via `handle_command`, so the log
+/// format is simply the line-oriented command protocol already spoken by
+/// the TCP server.
+pub struct RaftNode {
+    pub node_id: u32,
+    pub peers: Vec<String>,
+    pub db: Arc<HyperionDB>,
+    pub role: RwLock<Role>,
+    pub current_term: AtomicU64,
+    pub commit_index: AtomicU64,
+    pub log: RwLock<Vec<LogEntry>>,
+}
+
+impl RaftNode {
+    /// Creates a new node. Nodes are started as `Leader` by default since
+    /// HyperionDB clusters are statically configured rather than elected at
+    /// boot; `append_entry` moves a node to `Follower` when it receives an
+    /// entry from another node.
+    pub fn new(node_id: u32, peers: Vec<String>, db: Arc<HyperionDB>) -> Self {
+        RaftNode {
+            node_id,
+            peers,
+            db,
+            role: RwLock::new(Role::Leader),
+            current_term: AtomicU64::new(0),
+            commit_index: AtomicU64::new(0),
+            log: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Proposes a command to the cluster. Only the leader may propose; the
+    /// entry is appended to the local log, replicated to every peer, and
+    /// applied to the local database once a majority of the cluster
+    /// (including this node) has acknowledged it.
+    pub async fn propose(self: &Arc<Self>, command: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        if *self.role.read().await != Role::Leader {
+            return Err(Box::new(NotLeaderError));
+        }
+
+        let term = self.current_term.load(Ordering::SeqCst);
+        let index = {
+            let mut log = self.log.write().await;
+            log.push(LogEntry {
+                term,
+                command: command.clone(),
+            });
+            log.len() as u64
+        };
+
+        let mut acks = 1u32; // counts itself
+        for peer in &self.peers {
+            if Self::send_append_entries(peer, term, index, &command).await.is_ok() {
+                acks += 1;
+            }
+        }
+
+        let majority = (self.peers.len() as u32 + 1) / 2 + 1;
+        if acks < majority {
+            return Err("failed to reach quorum".into());
+        }
+
+        self.commit_index.store(index, Ordering::SeqCst);
+        handle_command(&self.db, command)
+            .await
+            .map_err(|e| e.to_string().into())
+    }
+
+    async fn send_append_entries(
+        peer: &str,
+        term: u64,
+        index: u64,
+        command: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut stream = TcpStream::connect(peer).await?;
+        let line = format!("RAFT_APPEND {} {} {}\n", term, index, command);
+        stream.write_all(line.as_bytes()).await?;
+
+        let mut reader = BufReader::new(stream);
+        let mut response = String::new();
+        reader.read_line(&mut response).await?;
+
+        if response.trim_start().starts_with("OK") {
+            Ok(())
+        } else {
+            Err("peer rejected entry".into())
+        }
+    }
+
+    /// Applies a log entry received from the leader over `RAFT_APPEND`,
+    /// stepping down to `Follower` for the new term if needed.
+    pub async fn append_entry(&self, term: u64, command: String) -> Result<String, Box<dyn Error>> {
+        self.current_term.fetch_max(term, Ordering::SeqCst);
+        {
+            let mut log = self.log.write().await;
+            log.push(LogEntry {
+                term,
+                command: command.clone(),
+            });
+        }
+        *self.role.write().await = Role::Follower;
+
+        handle_command(&self.db, command).await
+    }
+
+    /// Starts a dedicated listener that accepts `RAFT_APPEND` replication
+    /// traffic from peers on `addr`. Runs until the process exits.
+    pub async fn serve(self: Arc<Self>, addr: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let listener = TcpListener::bind(addr).await?;
+
+        loop {
+            let (socket, _) = listener.accept().await?;
+            let node = self.clone();
+
+            tokio::spawn(async move {
+                let (reader, mut writer) = socket.into_split();
+                let mut reader = BufReader::new(reader);
+                let mut line = String::new();
+
+                while let Ok(bytes_read) = reader.read_line(&mut line).await {
+                    if bytes_read == 0 {
+                        break;
+                    }
+
+                    let response = match node.handle_append_line(line.trim()).await {
+                        Ok(resp) => format!("OK {}\n", resp),
+                        Err(e) => format!("ERR {}\n", e),
+                    };
+
+                    if writer.write_all(response.as_bytes()).await.is_err() {
+                        break;
+                    }
+
+                    line.clear();
+                }
+            });
+        }
+    }
+
+    async fn handle_append_line(&self, line: &str) -> Result<String, Box<dyn Error>> {
+        let parts: Vec<&str> = line.splitn(4, ' ').collect();
+        if parts.get(0) != Some(&"RAFT_APPEND") {
+            return Err("expected RAFT_APPEND".into());
+        }
+        let term: u64 = parts.get(1).ok_or("missing term")?.parse()?;
+        let _index: u64 = parts.get(2).ok_or("missing index")?.parse()?;
+        let command = parts.get(3).map(|s| s.to_string()).ok_or("missing command")?;
+        self.append_entry(term, command).await
+    }
+}
@@ -4,12 +4,17 @@ use std::collections::HashMap;
 use std::error::Error;
 use tokio::sync::RwLock;
 use std::sync::Arc;
-use crate::storage::load_shard_from_disk; // Import function from storage module
+use crate::storage::{backend_from_uri, StorageBackend};
 
 pub struct ShardManager {
     pub data_dir: String,
     pub num_shards: u32,
     pub shards: Arc<RwLock<HashMap<u32, Arc<DashMap<String, Value>>>>>, // Cambiamos aquí el tipo
+    /// Pluggable persistence backend, selected from `data_dir`'s URI scheme.
+    /// Behind a lock so the `CONVERT` command can swap it out for a
+    /// different engine on a running instance, the same way `RELOAD` swaps
+    /// `HyperionDB::indexed_fields`.
+    pub backend: RwLock<Arc<dyn StorageBackend>>,
 }
 
 impl std::ops::Deref for ShardManager {
@@ -23,11 +28,12 @@ impl std::ops::Deref for ShardManager {
 impl ShardManager {
     pub async fn new(num_shards: u32, data_dir: String) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let shards = Arc::new(RwLock::new(HashMap::new()));
+        let backend = backend_from_uri(&data_dir);
 
         for shard_id in 0..num_shards {
-            // Cargar shard_data desde disco como HashMap
-            let shard_data = load_shard_from_disk(&data_dir, shard_id).await?;
-            println!("Shard {}: Cargados {} registros desde disco.", shard_id, shard_data.len());
+            // Cargar shard_data usando el backend de almacenamiento configurado
+            let shard_data = backend.load_shard(shard_id).await?;
+            println!("Shard {}: Cargados {} registros desde almacenamiento.", shard_id, shard_data.len());
 
             // Convertimos shard_data a un DashMap y luego lo envolvemos en un Arc
             let shard = Arc::new(DashMap::from_iter(shard_data.into_iter()));
@@ -38,6 +44,7 @@ impl ShardManager {
             data_dir,
             num_shards,
             shards,
+            backend: RwLock::new(backend),
         })
     }
 
@@ -47,4 +54,28 @@ impl ShardManager {
         key.hash(&mut hasher);
         (hasher.finish() as u32) % self.num_shards
     }
+
+    /// Persists `shard` via the configured storage backend.
+    pub async fn persist_shard(
+        &self,
+        shard_id: u32,
+        shard: Arc<DashMap<String, Value>>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.backend.read().await.save_shard(shard_id, shard).await
+    }
+
+    /// Loads `shard_id` via the configured storage backend.
+    pub async fn load_shard(
+        &self,
+        shard_id: u32,
+    ) -> Result<HashMap<String, Value>, Box<dyn Error + Send + Sync>> {
+        self.backend.read().await.load_shard(shard_id).await
+    }
+
+    /// Swaps in a different storage backend for all future loads/saves,
+    /// used by `CONVERT` once it has finished migrating existing shards to
+    /// the new engine.
+    pub async fn set_backend(&self, backend: Arc<dyn StorageBackend>) {
+        *self.backend.write().await = backend;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,182 @@
+use dashmap::DashMap;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+const MIN_CHUNK_SIZE: usize = 1024;
+const MAX_CHUNK_SIZE: usize = 8192;
+/// Chosen so the expected chunk size is ~8KB.
+const BOUNDARY_MASK: u32 = (1 << 13) - 1;
+/// Width of the buzhash sliding window: the boundary decision at any byte
+/// depends on exactly this many trailing bytes, no more.
+const WINDOW_SIZE: usize = 64;
+const ROTATE_BY_WINDOW: u32 = (WINDOW_SIZE as u32) % 32;
+
+fn hash_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Maps a byte value to a well-mixed 32-bit word for `chunk_bytes`' buzhash,
+/// avoiding the need to bake in a 256-entry random lookup table.
+fn buzhash_word(byte: u8) -> u32 {
+    let mut h = (byte as u32).wrapping_mul(0x9E3779B1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85EBCA6B);
+    h ^= h >> 13;
+    h
+}
+
+/// Splits `data` into content-defined chunks using a buzhash rolling hash
+/// over a fixed `WINDOW_SIZE`-byte trailing window, so that identical runs
+/// of bytes produce identical chunk boundaries no matter where they appear,
+/// and an edit only perturbs boundary decisions within `WINDOW_SIZE` bytes
+/// of it rather than everywhere after it in the chunk. This is what makes
+/// dedup across records possible, unlike fixed-size chunking which shifts
+/// every following boundary on any edit.
+pub fn chunk_bytes(data: &[u8]) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut rolling_hash: u32 = 0;
+
+    for (i, byte) in data.iter().enumerate() {
+        rolling_hash = rolling_hash.rotate_left(1) ^ buzhash_word(*byte);
+        let len = i - start + 1;
+        if len > WINDOW_SIZE {
+            let outgoing = data[i - WINDOW_SIZE];
+            rolling_hash ^= buzhash_word(outgoing).rotate_left(ROTATE_BY_WINDOW);
+        }
+        if (len >= MIN_CHUNK_SIZE && rolling_hash & BOUNDARY_MASK == 0) || len >= MAX_CHUNK_SIZE {
+            chunks.push(&data[start..=i]);
+            start = i + 1;
+            rolling_hash = 0;
+        }
+    }
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+
+    chunks
+}
+
+/// Content-addressed store for de-duplicated chunks shared across records.
+///
+/// Large JSON values are split into content-defined chunks before being
+/// persisted; any chunk whose hash already exists is reused instead of
+/// stored again, so repeated sub-values (common in similar records) are
+/// only kept once. Each chunk is refcounted by how many manifests
+/// currently reference it, so `release` (called from `delete`/`delete_all`
+/// once a manifest record is removed) can reclaim a chunk as soon as
+/// nothing points to it anymore.
+pub struct ChunkStore {
+    chunks: DashMap<String, (Vec<u8>, usize)>,
+    log_path: String,
+}
+
+impl ChunkStore {
+    /// Loads `<data_dir>/chunks.log` if present -- an append-only log of
+    /// every distinct chunk ever seen, written once per hash the first time
+    /// `put` encounters it. Refcounts aren't logged; like the in-memory
+    /// indices, they're rebuilt by `HyperionDB::new` re-walking the loaded
+    /// shards and calling `adopt` for each chunk manifest it finds.
+    pub async fn load(data_dir: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let log_path = format!("{}/chunks.log", data_dir);
+        let chunks = DashMap::new();
+
+        if let Ok(file) = tokio::fs::File::open(&log_path).await {
+            let mut lines = BufReader::new(file).lines();
+            while let Some(line) = lines.next_line().await? {
+                if let Ok((hash, bytes)) = serde_json::from_str::<(String, Vec<u8>)>(&line) {
+                    chunks.insert(hash, (bytes, 0));
+                }
+            }
+        }
+
+        Ok(ChunkStore { chunks, log_path })
+    }
+
+    async fn append_chunk(&self, hash: &str, bytes: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(parent) = std::path::Path::new(&self.log_path).parent() {
+            tokio::fs::create_dir_all(parent).await.ok();
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .await?;
+        let line = serde_json::to_string(&(hash, bytes))?;
+        file.write_all(line.as_bytes()).await?;
+        file.write_all(b"\n").await?;
+        Ok(())
+    }
+
+    /// Chunks `data`, storing (and logging to disk) any not-yet-seen chunk,
+    /// bumping the refcount of every chunk it uses, and returns the ordered
+    /// list of chunk hashes needed to reconstruct it.
+    pub async fn put(&self, data: &[u8]) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let mut hashes = Vec::new();
+        for chunk in chunk_bytes(data) {
+            let hash = hash_hex(chunk);
+            let is_new = !self.chunks.contains_key(&hash);
+            if is_new {
+                self.append_chunk(&hash, chunk).await?;
+            }
+            self.chunks
+                .entry(hash.clone())
+                .and_modify(|(_, refcount)| *refcount += 1)
+                .or_insert_with(|| (chunk.to_vec(), 1));
+            hashes.push(hash);
+        }
+        Ok(hashes)
+    }
+
+    /// Bumps the refcount of each already-stored hash in `hashes` without
+    /// touching the on-disk log. Like the in-memory indices, refcounts
+    /// aren't persisted directly -- `HyperionDB::new` calls this once per
+    /// manifest record found while re-walking the loaded shards, which
+    /// rebuilds them from how many manifests actually reference each chunk.
+    pub fn adopt(&self, hashes: &[String]) {
+        for hash in hashes {
+            if let Some(mut entry) = self.chunks.get_mut(hash) {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    /// Reassembles the original bytes from an ordered list of chunk hashes.
+    pub fn get(&self, hashes: &[String]) -> Option<Vec<u8>> {
+        let mut data = Vec::new();
+        for hash in hashes {
+            data.extend_from_slice(&self.chunks.get(hash)?.value().0);
+        }
+        Some(data)
+    }
+
+    /// Decrements the refcount of every hash in `hashes`, dropping any chunk
+    /// that reaches zero. Called once a manifest record referencing them has
+    /// been removed from its shard, so no record points at the chunk anymore.
+    pub fn release(&self, hashes: &[String]) {
+        for hash in hashes {
+            let drop_chunk = match self.chunks.get_mut(hash) {
+                Some(mut entry) => {
+                    entry.1 = entry.1.saturating_sub(1);
+                    entry.1 == 0
+                }
+                None => false,
+            };
+            if drop_chunk {
+                self.chunks.remove(hash);
+            }
+        }
+    }
+
+    /// Number of distinct chunks currently retained, i.e. after dedup.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+}
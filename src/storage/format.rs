@@ -0,0 +1,31 @@
+/// Magic bytes identifying a versioned HyperionDB shard file.
+pub const FORMAT_MAGIC: &[u8; 4] = b"HDB1";
+
+/// Current on-disk shard format version. Bump this whenever the shard file
+/// layout changes, and teach `load_shard_from_disk` to keep reading older
+/// versions so existing data directories upgrade in place.
+pub const CURRENT_FORMAT_VERSION: u8 = 2;
+
+/// Shard files written before format versioning was introduced: a bare
+/// lz4-compressed payload with no header at all.
+pub const LEGACY_FORMAT_VERSION: u8 = 1;
+
+/// Prefixes `payload` with the versioned shard file header.
+pub fn write_header(version: u8, payload: Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(FORMAT_MAGIC.len() + 1 + payload.len());
+    out.extend_from_slice(FORMAT_MAGIC);
+    out.push(version);
+    out.extend_from_slice(&payload);
+    out
+}
+
+/// Splits a shard file's raw bytes into `(version, payload)`. Files that
+/// don't start with `FORMAT_MAGIC` are assumed to be legacy (version 1)
+/// files predating format versioning, and are returned unchanged.
+pub fn read_header(data: &[u8]) -> (u8, &[u8]) {
+    if data.len() > FORMAT_MAGIC.len() && &data[..FORMAT_MAGIC.len()] == FORMAT_MAGIC {
+        (data[FORMAT_MAGIC.len()], &data[FORMAT_MAGIC.len() + 1..])
+    } else {
+        (LEGACY_FORMAT_VERSION, data)
+    }
+}
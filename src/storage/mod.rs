@@ -1,7 +1,15 @@
+pub mod backend;
+pub mod chunking;
+pub mod format;
+pub use backend::{backend_from_uri, StorageBackend};
+pub use chunking::ChunkStore;
+pub use format::CURRENT_FORMAT_VERSION;
+
 use lz4::EncoderBuilder;
 use serde_json::Value;
 use std::{collections::HashMap, io::Write};
 use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::Mutex;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, AsyncBufReadExt, BufReader};
 use dashmap::DashMap;
@@ -12,35 +20,122 @@ use tokio::fs::File;
 use std::error::Error as StdError;
 
 /// WalManager gestiona la concurrencia para el archivo WAL, usando un Mutex por cada shard.
+///
+/// Every WAL line is prefixed with a sequence number from `seq_counters`,
+/// monotonically increasing per shard. `checkpoint` uses it to only discard
+/// entries a snapshot already covers, instead of truncating the whole file
+/// unconditionally -- a write that lands between the snapshot being taken
+/// and the truncate running would otherwise be lost forever.
 pub struct WalManager {
     wal_mutexes: HashMap<u32, Arc<Mutex<()>>>,
+    seq_counters: HashMap<u32, Arc<AtomicU64>>,
 }
 
 impl WalManager {
     /// Crea un nuevo WalManager con Mutex por cada shard_id.
     pub fn new(shard_ids: Vec<u32>) -> Self {
         let mut wal_mutexes = HashMap::new();
+        let mut seq_counters = HashMap::new();
         for shard_id in shard_ids {
             wal_mutexes.insert(shard_id, Arc::new(Mutex::new(())));
+            seq_counters.insert(shard_id, Arc::new(AtomicU64::new(0)));
+        }
+        WalManager { wal_mutexes, seq_counters }
+    }
+
+    /// The last sequence number assigned to `shard_id`, i.e. the highest
+    /// one any durably-written WAL entry for it can have. Sampled by
+    /// `checkpoint_shard` *before* taking the in-memory snapshot, so the
+    /// checkpoint_seq it then passes to `checkpoint` is guaranteed to be
+    /// covered by that snapshot.
+    pub fn current_seq(&self, shard_id: u32) -> u64 {
+        self.seq_counters
+            .get(&shard_id)
+            .map(|counter| counter.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
+    /// Seeds a shard's sequence counter on startup, so freshly appended
+    /// entries keep numbering up from where the recovered WAL/snapshot left
+    /// off rather than restarting at zero.
+    pub fn seed_seq(&self, shard_id: u32, seq: u64) {
+        if let Some(counter) = self.seq_counters.get(&shard_id) {
+            counter.fetch_max(seq, Ordering::SeqCst);
+        }
+    }
+
+    /// Checkpoints a shard: once its in-memory state has been durably
+    /// persisted via the storage backend, any WAL entry with a sequence
+    /// number `<= checkpoint_seq` is already reflected in that snapshot and
+    /// can be dropped; anything after it is kept, since it raced the
+    /// snapshot and isn't covered yet. The remaining entries are written to
+    /// a temp file, fsynced, and renamed over the WAL, so a crash mid-compact
+    /// never leaves a truncated-but-not-yet-replaced file behind.
+    pub async fn checkpoint(
+        &self,
+        data_dir: &str,
+        shard_id: u32,
+        checkpoint_seq: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let wal_file_path = format!("{}/shard_{}.wal", data_dir, shard_id);
+        let tmp_path = format!("{}.tmp", wal_file_path);
+
+        if let Some(mutex) = self.wal_mutexes.get(&shard_id) {
+            let _lock = mutex.lock().await;
+
+            let mut retained = Vec::new();
+            if let Ok(file) = File::open(&wal_file_path).await {
+                let mut lines = BufReader::new(file).lines();
+                while let Some(line) = lines.next_line().await? {
+                    if let Ok((seq, _, _)) = serde_json::from_str::<(u64, String, Option<Value>)>(&line) {
+                        if seq > checkpoint_seq {
+                            retained.push(line);
+                        }
+                    }
+                }
+            }
+
+            let mut tmp_file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&tmp_path)
+                .await?;
+            for line in &retained {
+                tmp_file.write_all(line.as_bytes()).await?;
+                tmp_file.write_all(b"\n").await?;
+            }
+            tmp_file.sync_all().await?;
+
+            tokio::fs::rename(&tmp_path, &wal_file_path).await?;
         }
-        WalManager { wal_mutexes }
+
+        Ok(())
     }
 
     /// Añade una entrada al archivo WAL, garantizando que solo una tarea a la vez pueda escribir.
+    /// `value: None` records a delete (a tombstone), replayed by
+    /// `load_from_wal` as a removal rather than an insert.
     pub async fn append_to_wal(
         &self,
         data_dir: &str,
         shard_id: u32,
         key: String,
-        value: Value,
+        value: Option<Value>,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         let wal_file_path = format!("{}/shard_{}.wal", data_dir, shard_id);
-        let serialized_entry = serde_json::to_string(&(key, value))?;
 
         // Obtenemos el Mutex correspondiente al shard y lo bloqueamos durante la escritura
         if let Some(mutex) = self.wal_mutexes.get(&shard_id) {
             let _lock = mutex.lock().await; // Bloqueo hasta que finalice la escritura
 
+            let seq = self
+                .seq_counters
+                .get(&shard_id)
+                .map(|counter| counter.fetch_add(1, Ordering::SeqCst) + 1)
+                .unwrap_or(1);
+            let serialized_entry = serde_json::to_string(&(seq, key, value))?;
+
             let mut file = tokio::fs::OpenOptions::new()
                 .create(true)
                 .append(true)
@@ -54,21 +149,39 @@ impl WalManager {
     }
 }
 
-/// Carga los datos desde el archivo WAL al shard correspondiente.
+/// Carga los datos desde el archivo WAL al shard correspondiente, replaying
+/// only entries with a sequence number greater than `since_seq` (the
+/// checkpoint_seq already covered by the loaded snapshot). Returns the
+/// highest sequence number seen, so the caller can seed `WalManager`'s
+/// counter to keep numbering up from there.
 pub async fn load_from_wal(
+    data_dir: &str,
     shard: &Arc<DashMap<String, Value>>,
     shard_id: u32,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let wal_file = format!("hyperiondb_data/shard_{}.wal", shard_id);
+    since_seq: u64,
+) -> Result<u64, Box<dyn Error + Send + Sync>> {
+    let wal_file = format!("{}/shard_{}.wal", data_dir, shard_id);
+    let mut max_seq = since_seq;
 
     if let Ok(file) = File::open(&wal_file).await {
         let reader = BufReader::new(file);
         let mut lines = reader.lines();
 
         while let Some(line) = lines.next_line().await? {
-            match serde_json::from_str::<(String, Value)>(&line) {
-                Ok((key, value)) => {
-                    shard.insert(key, value); // Inserta el dato en el shard
+            match serde_json::from_str::<(u64, String, Option<Value>)>(&line) {
+                Ok((seq, key, value)) => {
+                    max_seq = max_seq.max(seq);
+                    if seq <= since_seq {
+                        continue;
+                    }
+                    match value {
+                        Some(value) => {
+                            shard.insert(key, value); // Inserta el dato en el shard
+                        }
+                        None => {
+                            shard.remove(&key); // Tombstone: el registro fue eliminado
+                        }
+                    }
                 }
                 Err(e) => {
                     eprintln!("Error al deserializar línea en WAL para shard {}: {:?}", shard_id, e);
@@ -78,6 +191,39 @@ pub async fn load_from_wal(
         }
     }
 
+    Ok(max_seq)
+}
+
+/// Reads the `checkpoint_seq` recorded by the last successful
+/// `checkpoint_shard` for `shard_id`, or `0` if the shard has never been
+/// checkpointed. Kept as a small sidecar file next to the shard snapshot
+/// rather than inside it, so the shard file format (and the other storage
+/// backends that don't go through `save_shard_to_disk`) don't need to know
+/// about it.
+pub async fn read_checkpoint_seq(data_dir: &str, shard_id: u32) -> u64 {
+    let path = format!("{}/shard_{}.checkpoint_seq", data_dir, shard_id);
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents.trim().parse().unwrap_or(0),
+        Err(_) => 0,
+    }
+}
+
+/// Durably records `checkpoint_seq` as the sequence number a shard's
+/// snapshot now covers, via the same tmp-write+fsync+rename sequence as the
+/// WAL compaction it accompanies.
+pub async fn write_checkpoint_seq(
+    data_dir: &str,
+    shard_id: u32,
+    checkpoint_seq: u64,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let path = format!("{}/shard_{}.checkpoint_seq", data_dir, shard_id);
+    let tmp_path = format!("{}.tmp", path);
+
+    let mut tmp_file = tokio::fs::File::create(&tmp_path).await?;
+    tmp_file.write_all(checkpoint_seq.to_string().as_bytes()).await?;
+    tmp_file.sync_all().await?;
+    tokio::fs::rename(&tmp_path, &path).await?;
+
     Ok(())
 }
 
@@ -89,10 +235,12 @@ pub async fn load_shard_from_disk(
     let data_file = format!("{}/shard_{}.bin.lz4", data_dir, shard_id);
     if tokio::fs::try_exists(&data_file).await.unwrap_or(false) {
         let mut file = tokio::fs::File::open(data_file).await?;
-        let mut compressed_data = Vec::new();
-        file.read_to_end(&mut compressed_data).await?;
+        let mut raw = Vec::new();
+        file.read_to_end(&mut raw).await?;
 
-        let mut decoder = Decoder::new(&compressed_data[..])?;
+        let (_version, compressed_data) = format::read_header(&raw);
+
+        let mut decoder = Decoder::new(compressed_data)?;
         let mut decompressed_data = Vec::new();
         decoder.read_to_end(&mut decompressed_data)?;
 
@@ -103,6 +251,23 @@ pub async fn load_shard_from_disk(
     }
 }
 
+/// Reports the on-disk format version of a shard file, for use by the
+/// `UPGRADE` command without decompressing the whole shard.
+pub async fn shard_format_version(
+    data_dir: &str,
+    shard_id: u32,
+) -> Result<Option<u8>, Box<dyn Error + Send + Sync>> {
+    let data_file = format!("{}/shard_{}.bin.lz4", data_dir, shard_id);
+    if !tokio::fs::try_exists(&data_file).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let mut file = tokio::fs::File::open(data_file).await?;
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw).await?;
+    Ok(Some(format::read_header(&raw).0))
+}
+
 /// Guarda el estado del shard en el disco de forma comprimida.
 pub async fn save_shard_to_disk(
     data_dir: &str,
@@ -133,7 +298,9 @@ pub async fn save_shard_to_disk(
     result
         .map_err(|e| Box::new(e) as Box<dyn StdError + Send + Sync + 'static>)?;
 
-    tokio::fs::write(data_file.clone(), compressed_data)
+    let versioned = format::write_header(format::CURRENT_FORMAT_VERSION, compressed_data);
+
+    tokio::fs::write(data_file.clone(), versioned)
         .await
         .map_err(|e| Box::new(e) as Box<dyn StdError + Send + Sync + 'static>)?;
 
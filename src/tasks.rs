@@ -0,0 +1,157 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Where a background task is in its lifecycle. Only moves forward:
+/// `Enqueued` -> `Processing` -> `Succeeded`/`Failed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+/// A background task's full record, appended to the task log on every
+/// status transition so `TASK`/`TASKS` can answer from the in-memory
+/// `TaskStore` without re-reading the log, while the log itself lets a
+/// restarted server recover the latest status of every task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub kind: String,
+    pub status: TaskStatus,
+    pub enqueued_at_ms: u128,
+    pub started_at_ms: Option<u128>,
+    pub finished_at_ms: Option<u128>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()
+}
+
+/// Tracks long-running operations (`IMPORT`, `REINDEX`) that are spawned
+/// onto their own `tokio` task instead of running on the request/response
+/// connection, so a client can poll `TASK <id>` for progress rather than
+/// holding a socket open. Every transition is appended to `tasks.log` in
+/// `data_dir` so status survives a restart; `TaskStore::load` replays that
+/// log, keeping only the last (most advanced) record per task id.
+pub struct TaskStore {
+    tasks: DashMap<u64, TaskInfo>,
+    next_id: AtomicU64,
+    log_path: String,
+}
+
+impl TaskStore {
+    /// Loads `<data_dir>/tasks.log` if present, replaying each appended
+    /// snapshot so the store picks up where a previous run left off.
+    pub async fn load(data_dir: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let log_path = format!("{}/tasks.log", data_dir);
+        let tasks = DashMap::new();
+        let mut max_id = 0u64;
+
+        if let Ok(file) = tokio::fs::File::open(&log_path).await {
+            let mut lines = BufReader::new(file).lines();
+            while let Some(line) = lines.next_line().await? {
+                if let Ok(task) = serde_json::from_str::<TaskInfo>(&line) {
+                    max_id = max_id.max(task.id);
+                    tasks.insert(task.id, task);
+                }
+            }
+        }
+
+        Ok(TaskStore {
+            tasks,
+            next_id: AtomicU64::new(max_id + 1),
+            log_path,
+        })
+    }
+
+    async fn append(&self, task: &TaskInfo) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(parent) = std::path::Path::new(&self.log_path).parent() {
+            tokio::fs::create_dir_all(parent).await.ok();
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .await?;
+        file.write_all(serde_json::to_string(task)?.as_bytes()).await?;
+        file.write_all(b"\n").await?;
+        Ok(())
+    }
+
+    /// Records a new `Enqueued` task of the given `kind` and returns its id.
+    pub async fn enqueue(&self, kind: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let task = TaskInfo {
+            id,
+            kind: kind.to_string(),
+            status: TaskStatus::Enqueued,
+            enqueued_at_ms: now_ms(),
+            started_at_ms: None,
+            finished_at_ms: None,
+            result: None,
+            error: None,
+        };
+        self.append(&task).await?;
+        self.tasks.insert(id, task);
+        Ok(id)
+    }
+
+    /// Marks `id` as `Processing`. No-op if the task is unknown.
+    pub async fn mark_processing(&self, id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(mut task) = self.tasks.get_mut(&id) else {
+            return Ok(());
+        };
+        task.status = TaskStatus::Processing;
+        task.started_at_ms = Some(now_ms());
+        let snapshot = task.clone();
+        drop(task);
+        self.append(&snapshot).await
+    }
+
+    /// Marks `id` as `Succeeded` with `result`. No-op if the task is unknown.
+    pub async fn mark_succeeded(&self, id: u64, result: Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(mut task) = self.tasks.get_mut(&id) else {
+            return Ok(());
+        };
+        task.status = TaskStatus::Succeeded;
+        task.finished_at_ms = Some(now_ms());
+        task.result = Some(result);
+        let snapshot = task.clone();
+        drop(task);
+        self.append(&snapshot).await
+    }
+
+    /// Marks `id` as `Failed` with `error`. No-op if the task is unknown.
+    pub async fn mark_failed(&self, id: u64, error: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(mut task) = self.tasks.get_mut(&id) else {
+            return Ok(());
+        };
+        task.status = TaskStatus::Failed;
+        task.finished_at_ms = Some(now_ms());
+        task.error = Some(error);
+        let snapshot = task.clone();
+        drop(task);
+        self.append(&snapshot).await
+    }
+
+    pub fn get(&self, id: u64) -> Option<TaskInfo> {
+        self.tasks.get(&id).map(|t| t.clone())
+    }
+
+    /// The `limit` most recently enqueued tasks, newest first.
+    pub fn recent(&self, limit: usize) -> Vec<TaskInfo> {
+        let mut tasks: Vec<TaskInfo> = self.tasks.iter().map(|entry| entry.value().clone()).collect();
+        tasks.sort_by(|a, b| b.id.cmp(&a.id));
+        tasks.truncate(limit);
+        tasks
+    }
+}
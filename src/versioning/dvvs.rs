@@ -0,0 +1,73 @@
+use super::vector_clock::VersionVector;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A single writer's identity for one value: the node that wrote it and
+/// that node's counter at the time, used to detect when a write has since
+/// been causally superseded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dot {
+    pub node_id: u32,
+    pub counter: u64,
+}
+
+/// One sibling value stored under a key, tagged with the dot that produced
+/// it. `value: None` marks a tombstone -- a delete that concurrent writers
+/// haven't yet observed, kept around so it can't be silently resurrected by
+/// a write that's actually racing it rather than superseding it.
+#[derive(Debug, Clone)]
+struct Sibling {
+    dot: Dot,
+    value: Option<Value>,
+}
+
+/// All concurrent sibling values for one key, modeled on a dotted version
+/// vector set: each write/delete is tagged with a fresh dot, and applying a
+/// new write drops every sibling the caller's causal context already saw,
+/// so concurrent writers converge without one silently clobbering another.
+#[derive(Debug, Clone, Default)]
+pub struct DottedRecord {
+    siblings: Vec<Sibling>,
+    version: VersionVector,
+}
+
+impl DottedRecord {
+    pub fn new() -> Self {
+        DottedRecord {
+            siblings: Vec::new(),
+            version: VersionVector::new(),
+        }
+    }
+
+    /// Applies a write (`value = Some(..)`) or delete (`value = None`) made
+    /// with causal context `context`: every existing sibling whose dot is
+    /// dominated by `context` is dropped (the caller has already seen it),
+    /// then the new value is added under a fresh dot for `node_id`.
+    pub fn apply(&mut self, node_id: u32, context: &VersionVector, value: Option<Value>) -> Dot {
+        self.siblings.retain(|sibling| {
+            let mut seen = VersionVector::new();
+            seen.0.insert(sibling.dot.node_id, sibling.dot.counter);
+            !context.dominates_or_equal(&seen)
+        });
+
+        let counter = self.version.0.get(&node_id).copied().unwrap_or(0) + 1;
+        let dot = Dot { node_id, counter };
+
+        self.version.merge(context);
+        self.version.set_at_least(node_id, counter);
+
+        self.siblings.push(Sibling { dot, value });
+        dot
+    }
+
+    /// The surviving non-tombstone sibling values.
+    pub fn live_values(&self) -> Vec<Value> {
+        self.siblings.iter().filter_map(|s| s.value.clone()).collect()
+    }
+
+    /// The causal-context token summarizing every dot this record has seen,
+    /// to hand back to the client for its next write.
+    pub fn context(&self) -> VersionVector {
+        self.version.clone()
+    }
+}
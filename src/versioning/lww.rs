@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Last-writer-wins metadata attached to a key, used to make concurrent
+/// writes across replicas converge on the same value deterministically.
+///
+/// Writes are ordered by `timestamp` (millis since the Unix epoch); ties are
+/// broken by `node_id` so that every replica resolves the conflict the same
+/// way regardless of delivery order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LwwStamp {
+    pub timestamp: u128,
+    pub node_id: u32,
+}
+
+impl LwwStamp {
+    pub fn new(timestamp: u128, node_id: u32) -> Self {
+        LwwStamp { timestamp, node_id }
+    }
+
+    /// Returns `true` if `self` should win over `other`, i.e. `self` is the
+    /// write that should be kept.
+    pub fn wins_over(&self, other: &LwwStamp) -> bool {
+        (self.timestamp, self.node_id) >= (other.timestamp, other.node_id)
+    }
+}
+
+/// Generates `LwwStamp` timestamps for this node. A client could otherwise
+/// pin a key permanently by supplying an arbitrary future timestamp to
+/// `LWW_INSERT`, so the timestamp half of the stamp is always produced here
+/// rather than trusted from the request.
+///
+/// Each tick is `max(physical_now_ms, last_tick + 1)`: ordinarily just the
+/// wall clock, but if two writes land in the same millisecond (or the clock
+/// steps backwards) it instead advances one past whatever it last handed
+/// out, so stamps stay strictly increasing within this node regardless of
+/// wall-clock resolution or skew.
+pub struct HybridClock {
+    last: AtomicU64,
+}
+
+impl HybridClock {
+    pub fn new() -> Self {
+        HybridClock { last: AtomicU64::new(0) }
+    }
+
+    pub fn tick(&self) -> u128 {
+        let physical_now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let mut last = self.last.load(Ordering::SeqCst);
+        loop {
+            let next = physical_now.max(last + 1);
+            match self.last.compare_exchange(last, next, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return next as u128,
+                Err(actual) => last = actual,
+            }
+        }
+    }
+}
+
+impl Default for HybridClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
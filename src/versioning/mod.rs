@@ -0,0 +1,7 @@
+pub mod dvvs;
+pub mod lww;
+pub mod vector_clock;
+
+pub use dvvs::{Dot, DottedRecord};
+pub use lww::{HybridClock, LwwStamp};
+pub use vector_clock::{append_version, load_versions, VersionVector};
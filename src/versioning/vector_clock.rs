@@ -0,0 +1,101 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Per-node Lamport-style counters attached to a key, handed to clients as
+/// an opaque causal token: a client reads a value together with its token,
+/// then echoes that token back on a conditional write so the server can
+/// tell whether the write is based on everything it has seen so far, or is
+/// racing a concurrent update it didn't know about.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionVector(pub BTreeMap<u32, u64>);
+
+impl VersionVector {
+    pub fn new() -> Self {
+        VersionVector(BTreeMap::new())
+    }
+
+    /// Bumps this node's counter, recording that it produced a new write.
+    pub fn increment(&mut self, node_id: u32) {
+        *self.0.entry(node_id).or_insert(0) += 1;
+    }
+
+    /// Returns `true` if `self` has seen everything `other` has, i.e. every
+    /// counter in `other` is matched or exceeded in `self`. Missing entries
+    /// count as `0`.
+    pub fn dominates_or_equal(&self, other: &VersionVector) -> bool {
+        other
+            .0
+            .iter()
+            .all(|(node, count)| self.0.get(node).copied().unwrap_or(0) >= *count)
+    }
+
+    /// Bumps this node's counter up to at least `counter`, leaving it
+    /// unchanged if it's already higher.
+    pub fn set_at_least(&mut self, node_id: u32, counter: u64) {
+        let entry = self.0.entry(node_id).or_insert(0);
+        if counter > *entry {
+            *entry = counter;
+        }
+    }
+
+    /// Merges `other`'s counters into `self`, keeping the max per node.
+    pub fn merge(&mut self, other: &VersionVector) {
+        for (node, count) in &other.0 {
+            let entry = self.0.entry(*node).or_insert(0);
+            if *count > *entry {
+                *entry = *count;
+            }
+        }
+    }
+}
+
+/// Appends `key`'s latest version token to `<data_dir>/versions.log`, so
+/// `INSERT_IF_MATCH`'s causal context survives a restart. Like `TaskStore`,
+/// the log is append-only and `load_versions` replays it keeping only the
+/// last (highest) token written per key.
+pub async fn append_version(
+    data_dir: &str,
+    key: &str,
+    token: &VersionVector,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let log_path = format!("{}/versions.log", data_dir);
+    if let Some(parent) = std::path::Path::new(&log_path).parent() {
+        tokio::fs::create_dir_all(parent).await.ok();
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .await?;
+    let line = serde_json::to_string(&(key, token))?;
+    file.write_all(line.as_bytes()).await?;
+    file.write_all(b"\n").await?;
+
+    Ok(())
+}
+
+/// Loads `<data_dir>/versions.log` if present, replaying each appended
+/// token so every key's causal context picks up where the previous run left
+/// off instead of resetting to empty, which would let a stale
+/// `INSERT_IF_MATCH` trivially dominate.
+pub async fn load_versions(
+    data_dir: &str,
+) -> Result<DashMap<String, VersionVector>, Box<dyn Error + Send + Sync>> {
+    let log_path = format!("{}/versions.log", data_dir);
+    let versions = DashMap::new();
+
+    if let Ok(file) = tokio::fs::File::open(&log_path).await {
+        let mut lines = BufReader::new(file).lines();
+        while let Some(line) = lines.next_line().await? {
+            if let Ok((key, token)) = serde_json::from_str::<(String, VersionVector)>(&line) {
+                versions.insert(key, token);
+            }
+        }
+    }
+
+    Ok(versions)
+}